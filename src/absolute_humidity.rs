@@ -0,0 +1,61 @@
+use nutype::nutype;
+
+use crate::Error;
+
+/// Absolute humidity, mass of water vapor per volume of air in g/m³
+#[nutype(
+    validate(greater_or_equal = 0.0),
+    derive(
+        Display,
+        TryFrom,
+        AsRef,
+        Serialize,
+        Deserialize,
+        Copy,
+        Clone,
+        PartialEq,
+        Debug,
+        Into,
+        PartialOrd,
+    )
+)]
+pub struct AbsoluteHumidity(f64);
+
+impl Default for AbsoluteHumidity {
+    fn default() -> Self {
+        Self::try_new(0.0).unwrap()
+    }
+}
+
+impl AbsoluteHumidity {
+    /// # Errors
+    ///
+    /// Will return error if input is less than zero
+    pub fn from_grams_per_cubic_meter(h: f64) -> Result<Self, Error> {
+        Self::try_new(h).map_err(Into::into)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn grams_per_cubic_meter(self) -> f64 {
+        self.into_inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, absolute_humidity::AbsoluteHumidity};
+
+    #[test]
+    fn test_absolute_humidity() -> Result<(), Error> {
+        let h = AbsoluteHumidity::from_grams_per_cubic_meter(8.3)?;
+        assert_eq!(h.grams_per_cubic_meter(), 8.3);
+
+        let h = AbsoluteHumidity::from_grams_per_cubic_meter(-1.0);
+        assert_eq!(
+            &format!("{h:?}"),
+            "Err(AbsoluteHumidityError(GreaterOrEqualViolated))"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, fmt, fmt::Write};
+use time::OffsetDateTime;
+
+use crate::{
+    Error, StringType,
+    concentration::Concentration,
+    format_string,
+    language::{Language, Message},
+    timestamp,
+    weather_data::Coord,
+};
+
+/// Air Quality Index as reported by openweathermap.org, from 1 (Good) to 5
+/// (Very Poor)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum AirQualityIndex {
+    Good = 1,
+    Fair = 2,
+    Moderate = 3,
+    Poor = 4,
+    VeryPoor = 5,
+}
+
+impl AirQualityIndex {
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Good => "Good",
+            Self::Fair => "Fair",
+            Self::Moderate => "Moderate",
+            Self::Poor => "Poor",
+            Self::VeryPoor => "Very Poor",
+        }
+    }
+}
+
+impl TryFrom<u8> for AirQualityIndex {
+    type Error = Error;
+    fn try_from(item: u8) -> Result<Self, Self::Error> {
+        match item {
+            1 => Ok(Self::Good),
+            2 => Ok(Self::Fair),
+            3 => Ok(Self::Moderate),
+            4 => Ok(Self::Poor),
+            5 => Ok(Self::VeryPoor),
+            _ => Err(Error::InvalidValue(format_string!(
+                "{item} is not a valid air quality index"
+            ))),
+        }
+    }
+}
+
+impl From<AirQualityIndex> for u8 {
+    fn from(item: AirQualityIndex) -> Self {
+        item as Self
+    }
+}
+
+impl fmt::Display for AirQualityIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", u8::from(*self), self.description())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AirQualityMain {
+    pub aqi: AirQualityIndex,
+}
+
+/// Pollutant concentrations
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct AirComponents {
+    /// Carbon monoxide
+    pub co: Concentration,
+    /// Nitrogen monoxide
+    pub no: Concentration,
+    /// Nitrogen dioxide
+    pub no2: Concentration,
+    /// Ozone
+    pub o3: Concentration,
+    /// Sulphur dioxide
+    pub so2: Concentration,
+    /// Fine particulate matter
+    pub pm2_5: Concentration,
+    /// Coarse particulate matter
+    pub pm10: Concentration,
+    /// Ammonia
+    pub nh3: Concentration,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct AirQualityEntry {
+    #[serde(with = "timestamp")]
+    pub dt: OffsetDateTime,
+    pub main: AirQualityMain,
+    pub components: AirComponents,
+}
+
+/// Representation of Air Pollution data from openweathermap.org
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct AirQuality {
+    pub coord: Coord,
+    pub list: Vec<AirQualityEntry>,
+}
+
+impl AirQuality {
+    /// Write out formatted air quality information for the first entry
+    /// ```
+    /// use weather_util_rust::{air_quality::AirQuality, language::Language};
+    /// # use anyhow::Error;
+    /// # use std::io::Read;
+    /// # use std::fs::File;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut buf = String::new();
+    /// # let mut f = File::open("tests/air_pollution.json")?;
+    /// # f.read_to_string(&mut buf)?;
+    /// let data: AirQuality = serde_json::from_str(&buf)?;
+    ///
+    /// let buf = data.get_air_quality(Language::En);
+    ///
+    /// assert!(buf.starts_with("\nAir Quality Index: 2 (Fair)"));
+    /// assert!(buf.contains("PM2.5: 11.52 μg/m³"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn get_air_quality(&self, lang: Language) -> StringType {
+        let mut output = StringType::new();
+        let Some(entry) = self.list.first() else {
+            return output;
+        };
+        let c = &entry.components;
+        let aqi = entry.main.aqi;
+        writeln!(
+            output,
+            "\n{}: {} ({})",
+            lang.get(Message::AirQualityIndex),
+            u8::from(aqi),
+            lang.air_quality(aqi)
+        )
+        .unwrap_or(());
+        for (label, value) in [
+            ("PM2.5", c.pm2_5),
+            ("PM10", c.pm10),
+            ("O3", c.o3),
+            ("NO2", c.no2),
+            ("NO", c.no),
+            ("SO2", c.so2),
+            ("CO", c.co),
+            ("NH3", c.nh3),
+        ] {
+            let value = format_string!("{:0.2} μg/m³", value.micrograms_per_cubic_meter());
+            writeln!(output, "\t{label}: {}", lang.localize_decimal(&value)).unwrap_or(());
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{
+        Error,
+        air_quality::{AirQuality, AirQualityIndex},
+        language::Language,
+    };
+
+    #[test]
+    fn test_air_quality() -> Result<(), Error> {
+        let buf = include_str!("../tests/air_pollution_forecast.json");
+        let data: AirQuality = serde_json::from_str(buf)?;
+        assert_eq!(data.list.len(), 3);
+        assert_eq!(data.list[1].main.aqi, AirQualityIndex::Moderate);
+        assert_eq!(
+            data.list[1].components.pm2_5.micrograms_per_cubic_meter(),
+            27.4
+        );
+
+        let buf = data.get_air_quality(Language::En);
+        assert!(buf.contains("Air Quality Index: 2 (Fair)"));
+        assert!(buf.contains("\tCO: 347.14 μg/m³"));
+        let buf = data.get_air_quality(Language::De);
+        assert!(buf.contains("Luftqualitätsindex: 2 (mäßig)"));
+        assert!(buf.contains("\tCO: 347,14 μg/m³"));
+        assert!(buf.contains("\tPM2.5: "));
+
+        let serialized = serde_json::to_string(&data)?;
+        assert!(serialized.contains(r#""aqi":3"#));
+        assert_eq!(serde_json::from_str::<AirQuality>(&serialized)?, data);
+
+        let invalid =
+            include_str!("../tests/air_pollution.json").replace(r#""aqi":2"#, r#""aqi":6"#);
+        assert!(serde_json::from_str::<AirQuality>(&invalid).is_err());
+        assert_eq!(AirQuality::default().get_air_quality(Language::En), "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_air_quality_index() -> Result<(), Error> {
+        assert_eq!(AirQualityIndex::try_from(5)?, AirQualityIndex::VeryPoor);
+        assert_eq!(format!("{}", AirQualityIndex::VeryPoor), "5 (Very Poor)");
+        assert!(AirQualityIndex::try_from(0).is_err());
+        assert!(AirQualityIndex::try_from(6).is_err());
+        assert!(AirQualityIndex::Poor > AirQualityIndex::Good);
+        Ok(())
+    }
+}
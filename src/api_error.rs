@@ -0,0 +1,125 @@
+use serde::{Deserialize, Deserializer, Serialize, de};
+use std::fmt;
+
+use crate::{Error, StringType};
+
+/// Error body returned by openweathermap.org, e.g.
+/// `{"cod":401,"message":"Invalid API key..."}`, note that `cod` may be
+/// either a number or a string.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct ApiError {
+    #[serde(rename = "cod", deserialize_with = "deserialize_code")]
+    pub code: u16,
+    #[serde(default)]
+    pub message: StringType,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.code, self.message)
+    }
+}
+
+fn deserialize_code<'de, D>(deserializer: D) -> Result<u16, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Code {
+        Number(u16),
+        String(StringType),
+    }
+    match Code::deserialize(deserializer)? {
+        Code::Number(code) => Ok(code),
+        Code::String(code) => code.trim().parse().map_err(de::Error::custom),
+    }
+}
+
+impl ApiError {
+    /// Decode the body of a failed request, falling back to the http status
+    /// and raw body if it isn't a json error body.
+    /// ```
+    /// use weather_util_rust::api_error::ApiError;
+    ///
+    /// let error = ApiError::from_response(404, br#"{"cod":"404","message":"city not found"}"#);
+    /// assert_eq!(error.code, 404);
+    /// assert_eq!(&error.message, "city not found");
+    ///
+    /// let error = ApiError::from_response(502, b"Bad Gateway");
+    /// assert_eq!(error.code, 502);
+    /// assert_eq!(&error.message, "Bad Gateway");
+    /// ```
+    #[must_use]
+    pub fn from_response(status: u16, body: &[u8]) -> Self {
+        serde_json::from_slice(body).unwrap_or_else(|_| Self {
+            code: status,
+            message: String::from_utf8_lossy(body).trim().into(),
+        })
+    }
+
+    /// Decode an error body returned with a successful http status, returns
+    /// `None` unless the body is an error body with an error code.
+    #[must_use]
+    pub fn from_body(body: &[u8]) -> Option<Self> {
+        serde_json::from_slice::<Self>(body)
+            .ok()
+            .filter(|e| e.code >= 400)
+    }
+}
+
+impl From<ApiError> for Error {
+    fn from(error: ApiError) -> Self {
+        match error.code {
+            401 => Self::InvalidApiKey(error.message),
+            404 => Self::LocationNotFound(error.message),
+            429 => Self::RateLimited(error.message),
+            500..=599 => Self::ServerError(error),
+            _ => Self::ApiError(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, api_error::ApiError};
+
+    #[test]
+    fn test_api_error() -> Result<(), Error> {
+        let error: ApiError = serde_json::from_str(
+            r#"{"cod":401, "message": "Invalid API key. Please see https://openweathermap.org/faq#error401 for more info."}"#,
+        )?;
+        assert_eq!(error.code, 401);
+        assert!(error.message.starts_with("Invalid API key"));
+        assert!(matches!(error.into(), Error::InvalidApiKey(_)));
+
+        let error = ApiError::from_response(404, br#"{"cod":"404","message":"city not found"}"#);
+        assert_eq!(format!("{error}"), "404 city not found");
+        let error: Error = error.into();
+        assert_eq!(format!("{error}"), "Location Not Found: city not found");
+
+        let error = ApiError::from_response(429, br#"{"cod":429}"#);
+        assert!(matches!(error.into(), Error::RateLimited(_)));
+
+        let error: Error = ApiError::from_response(503, b"").into();
+        assert!(matches!(
+            error,
+            Error::ServerError(ApiError { code: 503, .. })
+        ));
+
+        let error: Error =
+            ApiError::from_response(400, br#"{"cod":"400","message":"wrong latitude"}"#).into();
+        assert_eq!(format!("{error}"), "Api Error 400 wrong latitude");
+
+        assert_eq!(ApiError::from_body(br#"{"cod":"200","message":0}"#), None);
+        assert_eq!(ApiError::from_body(br#"{"coord":{}}"#), None);
+        assert_eq!(
+            ApiError::from_body(br#"{"cod":"404","message":"city not found"}"#),
+            Some(ApiError {
+                code: 404,
+                message: "city not found".into()
+            })
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, fmt};
+
+use crate::{Error, format_string, speed::Speed};
+
+// Lower bound of each force in meters per second, starting with force 1
+const LOWER_BOUNDS_MPS: [f64; 12] = [
+    0.5, 1.6, 3.4, 5.5, 8.0, 10.8, 13.9, 17.2, 20.8, 24.5, 28.5, 32.7,
+];
+
+/// Beaufort wind force scale, from 0 (Calm) to 12 (Hurricane force)
+/// ```
+/// use weather_util_rust::{beaufort::Beaufort, speed::Speed};
+/// # use anyhow::Error;
+/// # fn main() -> Result<(), Error> {
+/// let b = Speed::from_mph(15.0)?.beaufort();
+/// assert_eq!(b, Beaufort::ModerateBreeze);
+/// assert_eq!(&b.to_string(), "Beaufort 4 moderate breeze");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum Beaufort {
+    Calm = 0,
+    LightAir = 1,
+    LightBreeze = 2,
+    GentleBreeze = 3,
+    ModerateBreeze = 4,
+    FreshBreeze = 5,
+    StrongBreeze = 6,
+    NearGale = 7,
+    Gale = 8,
+    StrongGale = 9,
+    Storm = 10,
+    ViolentStorm = 11,
+    Hurricane = 12,
+}
+
+impl Beaufort {
+    #[must_use]
+    pub fn from_speed(speed: Speed) -> Self {
+        let mps = speed.mps();
+        let force = LOWER_BOUNDS_MPS.iter().filter(|b| mps >= **b).count();
+        Self::try_from(u8::try_from(force).unwrap_or(12)).unwrap_or(Self::Hurricane)
+    }
+
+    #[must_use]
+    pub fn force(self) -> u8 {
+        self.into()
+    }
+
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Calm => "calm",
+            Self::LightAir => "light air",
+            Self::LightBreeze => "light breeze",
+            Self::GentleBreeze => "gentle breeze",
+            Self::ModerateBreeze => "moderate breeze",
+            Self::FreshBreeze => "fresh breeze",
+            Self::StrongBreeze => "strong breeze",
+            Self::NearGale => "near gale",
+            Self::Gale => "gale",
+            Self::StrongGale => "strong gale",
+            Self::Storm => "storm",
+            Self::ViolentStorm => "violent storm",
+            Self::Hurricane => "hurricane force",
+        }
+    }
+}
+
+impl TryFrom<u8> for Beaufort {
+    type Error = Error;
+    fn try_from(item: u8) -> Result<Self, Self::Error> {
+        match item {
+            0 => Ok(Self::Calm),
+            1 => Ok(Self::LightAir),
+            2 => Ok(Self::LightBreeze),
+            3 => Ok(Self::GentleBreeze),
+            4 => Ok(Self::ModerateBreeze),
+            5 => Ok(Self::FreshBreeze),
+            6 => Ok(Self::StrongBreeze),
+            7 => Ok(Self::NearGale),
+            8 => Ok(Self::Gale),
+            9 => Ok(Self::StrongGale),
+            10 => Ok(Self::Storm),
+            11 => Ok(Self::ViolentStorm),
+            12 => Ok(Self::Hurricane),
+            _ => Err(Error::InvalidValue(format_string!(
+                "{item} is not a valid beaufort force"
+            ))),
+        }
+    }
+}
+
+impl From<Beaufort> for u8 {
+    fn from(item: Beaufort) -> Self {
+        item as Self
+    }
+}
+
+impl fmt::Display for Beaufort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Beaufort {} {}", self.force(), self.description())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{Error, beaufort::Beaufort, speed::Speed};
+
+    #[test]
+    fn test_beaufort() -> Result<(), Error> {
+        for (mps, force) in [
+            (0.0, 0),
+            (0.49, 0),
+            (0.5, 1),
+            (3.3, 2),
+            (5.36, 3),
+            (6.7, 4),
+            (17.2, 8),
+            (32.6, 11),
+            (32.7, 12),
+            (80.0, 12),
+        ] {
+            assert_eq!(Speed::from_mps(mps)?.beaufort().force(), force);
+        }
+        assert_eq!(Beaufort::try_from(9)?, Beaufort::StrongGale);
+        assert!(Beaufort::try_from(13).is_err());
+
+        let b: Beaufort = serde_json::from_str("7")?;
+        assert_eq!(b, Beaufort::NearGale);
+        assert_eq!(&serde_json::to_string(&b)?, "7");
+        assert_eq!(&b.to_string(), "Beaufort 7 near gale");
+        Ok(())
+    }
+}
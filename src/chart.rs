@@ -0,0 +1,401 @@
+use std::fmt::Write;
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::{
+    StringType, format_string,
+    language::{Language, Message},
+    precipitation::Precipitation,
+    temperature::Temperature,
+    unit_system::{PrecipitationUnit, TemperatureUnit, UnitSystem},
+    weather_forecast::{ForecastEntry, WeatherForecast},
+};
+
+const LABEL_WIDTH: usize = 9;
+const MIN_PLOT_WIDTH: usize = 10;
+const DEFAULT_WIDTH: usize = 80;
+const DEFAULT_HEIGHT: usize = 8;
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+// Braille dot bits indexed by [row][column] within one 2x4 character cell
+const BRAILLE_DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+const BRAILLE_BLANK: u32 = 0x2800;
+
+/// Grid of braille characters, each character holds 2x4 pixels, pixel `(0,
+/// 0)` is the top left corner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrailleCanvas {
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+}
+
+impl BrailleCanvas {
+    /// Canvas of `width` by `height` characters
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![0; width * height],
+        }
+    }
+
+    #[must_use]
+    pub fn pixel_width(&self) -> usize {
+        self.width * 2
+    }
+
+    #[must_use]
+    pub fn pixel_height(&self) -> usize {
+        self.height * 4
+    }
+
+    /// Set pixel `(x, y)`, pixels outside the canvas are ignored
+    pub fn set(&mut self, x: usize, y: usize) {
+        if x < self.pixel_width() && y < self.pixel_height() {
+            self.cells[(y / 4) * self.width + x / 2] |= BRAILLE_DOTS[y % 4][x % 2];
+        }
+    }
+
+    /// Draw a straight line between two pixels
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn line(&mut self, from: (usize, usize), to: (usize, usize)) {
+        let (x0, y0) = (from.0 as f64, from.1 as f64);
+        let (x1, y1) = (to.0 as f64, to.1 as f64);
+        let steps = from.0.abs_diff(to.0).max(from.1.abs_diff(to.1)).max(1);
+        for step in 0..=steps {
+            let f = step as f64 / steps as f64;
+            let x = (x0 + (x1 - x0) * f).round() as usize;
+            let y = (y0 + (y1 - y0) * f).round() as usize;
+            self.set(x, y);
+        }
+    }
+
+    /// Row `row` of the canvas as braille characters
+    #[must_use]
+    pub fn row(&self, row: usize) -> String {
+        self.cells[row * self.width..(row + 1) * self.width]
+            .iter()
+            .map(|bits| char::from_u32(BRAILLE_BLANK + u32::from(*bits)).unwrap_or(' '))
+            .collect()
+    }
+}
+
+/// Braille line chart of forecast temperature with precipitation bars
+/// underneath and local dates along the time axis
+/// ```
+/// # use anyhow::Error;
+/// # use std::io::Read;
+/// # use std::fs::File;
+/// use weather_util_rust::{chart::ForecastChart, weather_forecast::WeatherForecast};
+/// # fn main() -> Result<(), Error> {
+/// # let mut buf = String::new();
+/// # let mut f = File::open("tests/forecast.json")?;
+/// # f.read_to_string(&mut buf)?;
+/// let data: WeatherForecast = serde_json::from_str(&buf)?;
+///
+/// let chart = ForecastChart::new(60).with_height(6).render(&data);
+/// assert!(chart.iter().skip(1).all(|l| l.trim_end().chars().count() <= 60));
+/// assert!(chart.iter().any(|l| l.contains("Mon 28")));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForecastChart {
+    width: usize,
+    height: usize,
+    units: UnitSystem,
+    lang: Language,
+}
+
+impl Default for ForecastChart {
+    fn default() -> Self {
+        Self::new(DEFAULT_WIDTH)
+    }
+}
+
+impl ForecastChart {
+    /// Chart fitting in `width` terminal columns
+    #[must_use]
+    pub fn new(width: usize) -> Self {
+        Self {
+            width,
+            height: DEFAULT_HEIGHT,
+            units: UnitSystem::default(),
+            lang: Language::default(),
+        }
+    }
+
+    /// Height of the temperature chart in terminal rows
+    #[must_use]
+    pub fn with_height(self, height: usize) -> Self {
+        Self {
+            height: height.max(2),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_units(self, units: UnitSystem) -> Self {
+        Self { units, ..self }
+    }
+
+    /// Language of the header and the day labels
+    #[must_use]
+    pub fn with_lang(self, lang: Language) -> Self {
+        Self { lang, ..self }
+    }
+
+    fn temperature_value(&self, t: Temperature) -> f64 {
+        match self.units.temperature {
+            TemperatureUnit::Celsius => t.celcius(),
+            TemperatureUnit::Fahrenheit | TemperatureUnit::FahrenheitCelsius => t.fahrenheit(),
+            TemperatureUnit::Kelvin => t.kelvin(),
+        }
+    }
+
+    fn temperature_suffix(&self) -> &'static str {
+        match self.units.temperature {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit | TemperatureUnit::FahrenheitCelsius => "°F",
+            TemperatureUnit::Kelvin => "K",
+        }
+    }
+
+    fn precipitation_value(&self, entry: &ForecastEntry) -> f64 {
+        let rain = entry.rain.and_then(|r| r.three_hour).unwrap_or_default();
+        let snow = entry.snow.and_then(|s| s.three_hour).unwrap_or_default();
+        let total: Precipitation = rain + snow;
+        match self.units.precipitation {
+            PrecipitationUnit::Mm => total.millimeters(),
+            PrecipitationUnit::In => total.inches(),
+        }
+    }
+
+    fn precipitation_suffix(&self) -> &'static str {
+        match self.units.precipitation {
+            PrecipitationUnit::Mm => "mm",
+            PrecipitationUnit::In => "in",
+        }
+    }
+
+    /// Render the chart, one line per element
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn render(&self, forecast: &WeatherForecast) -> Vec<StringType> {
+        let mut output = vec![format_string!(
+            "\n{} ({}, {} {}/3h):\n",
+            self.lang.get(Message::ForecastChart),
+            self.temperature_suffix(),
+            self.lang.get(Message::Precipitation),
+            self.precipitation_suffix(),
+        )];
+        let list = &forecast.list;
+        let (Some(first), Some(last)) = (list.first(), list.last()) else {
+            output.push(format_string!("\t{}\n", self.lang.get(Message::NoData)));
+            return output;
+        };
+        let fo: UtcOffset = forecast.city.timezone.into();
+        let plot_width = self
+            .width
+            .saturating_sub(LABEL_WIDTH + 1)
+            .max(MIN_PLOT_WIDTH);
+        let mut canvas = BrailleCanvas::new(plot_width, self.height);
+        let (pixel_width, pixel_height) = (canvas.pixel_width(), canvas.pixel_height());
+
+        let start = first.dt;
+        let span = (last.dt - start).as_seconds_f64().max(1.0);
+        let x_of = |dt: OffsetDateTime| {
+            ((dt - start).as_seconds_f64() / span * (pixel_width - 1) as f64).round() as usize
+        };
+
+        let temps: Vec<f64> = list
+            .iter()
+            .map(|e| self.temperature_value(e.main.temp))
+            .collect();
+        let min = temps.iter().copied().fold(f64::INFINITY, f64::min);
+        let mut max = temps.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        if (max - min).abs() < f64::EPSILON {
+            max = min + 1.0;
+        }
+        let y_of = |v: f64| ((max - v) / (max - min) * (pixel_height - 1) as f64).round() as usize;
+
+        let points: Vec<_> = list
+            .iter()
+            .zip(temps.iter())
+            .map(|(e, t)| (x_of(e.dt), y_of(*t)))
+            .collect();
+        canvas.set(points[0].0, points[0].1);
+        for pair in points.windows(2) {
+            canvas.line(pair[0], pair[1]);
+        }
+
+        let suffix = self.temperature_suffix();
+        for row in 0..self.height {
+            let label = if row == 0 {
+                format!("{max:.1}{suffix}")
+            } else if row == self.height - 1 {
+                format!("{min:.1}{suffix}")
+            } else {
+                String::new()
+            };
+            let axis = if label.is_empty() { '│' } else { '┤' };
+            output.push(format_string!(
+                "{label:>LABEL_WIDTH$}{axis}{}\n",
+                canvas.row(row)
+            ));
+        }
+
+        // each entry covers the columns up to the next entry
+        let columns: Vec<usize> = list.iter().map(|e| x_of(e.dt) / 2).collect();
+        let precip: Vec<f64> = list.iter().map(|e| self.precipitation_value(e)).collect();
+        let max_precip = precip.iter().copied().fold(0.0, f64::max);
+        let mut bars = String::new();
+        for col in 0..plot_width {
+            let index = columns.iter().rposition(|c| *c <= col).unwrap_or(0);
+            let p = precip[index];
+            if p > 0.0 && max_precip > 0.0 {
+                let level = ((p / max_precip) * BARS.len() as f64).ceil() as usize;
+                bars.push(BARS[level.clamp(1, BARS.len()) - 1]);
+            } else {
+                bars.push(' ');
+            }
+        }
+        let label = format!("{max_precip:.2}{}", self.precipitation_suffix());
+        output.push(format_string!(
+            "{label:>LABEL_WIDTH$}┤{}\n",
+            bars.trim_end()
+        ));
+
+        let mut ticks = Vec::new();
+        let mut date = first.dt.to_offset(fo).date();
+        while let Some(next) = date.next_day() {
+            let midnight = next.midnight().assume_offset(fo);
+            if midnight > last.dt {
+                break;
+            }
+            ticks.push((x_of(midnight) / 2, next));
+            date = next;
+        }
+        let mut axis = format!("{:LABEL_WIDTH$}└", "");
+        let mut labels: Vec<char> = vec![' '; plot_width];
+        let mut free_from = 0;
+        for col in 0..plot_width {
+            axis.push(if ticks.iter().any(|(c, _)| *c == col) {
+                '┬'
+            } else {
+                '─'
+            });
+        }
+        for (col, date) in &ticks {
+            let label = format!("{} {:02}", self.lang.weekday(date.weekday()), date.day());
+            let len = label.chars().count();
+            if *col >= free_from && col + len <= plot_width {
+                for (i, c) in label.chars().enumerate() {
+                    labels[col + i] = c;
+                }
+                free_from = col + len + 1;
+            }
+        }
+        output.push(format_string!("{axis}\n"));
+        let labels: String = labels.into_iter().collect();
+        let mut line = StringType::new();
+        writeln!(line, "{:LABEL_WIDTH$} {}", "", labels.trim_end()).unwrap_or(());
+        output.push(line);
+        output
+    }
+}
+
+impl WeatherForecast {
+    /// Temperature and precipitation chart fitting in `width` terminal
+    /// columns
+    #[must_use]
+    pub fn get_chart(&self, width: usize, units: UnitSystem, lang: Language) -> Vec<StringType> {
+        ForecastChart::new(width)
+            .with_units(units)
+            .with_lang(lang)
+            .render(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Error,
+        chart::{BrailleCanvas, ForecastChart},
+        language::Language,
+        unit_system::Units,
+        weather_forecast::WeatherForecast,
+    };
+
+    #[test]
+    fn test_braille_canvas() {
+        let mut canvas = BrailleCanvas::new(2, 1);
+        assert_eq!(canvas.row(0), "\u{2800}\u{2800}");
+        canvas.set(0, 0);
+        canvas.set(3, 3);
+        assert_eq!(canvas.row(0), "\u{2801}\u{2880}");
+        canvas.set(10, 10);
+
+        let mut canvas = BrailleCanvas::new(2, 1);
+        canvas.line((0, 0), (3, 3));
+        assert_eq!(canvas.row(0), "⠑⢄");
+    }
+
+    #[test]
+    fn test_forecast_chart() -> Result<(), Error> {
+        let buf = include_str!("../tests/forecast.json");
+        let data: WeatherForecast = serde_json::from_str(buf)?;
+        let chart = ForecastChart::new(80).with_units(Units::Metric.into());
+        let output = chart.render(&data);
+        // header, 8 temperature rows, precipitation, axis and labels
+        assert_eq!(output.len(), 12);
+        assert!(output[0].contains("°C"));
+        assert!(output[1].starts_with("    9.1°C┤"));
+        assert!(output[8].starts_with("   -5.4°C┤"));
+        for line in &output[1..] {
+            assert!(line.ends_with('\n'));
+            assert!(line.trim_end().chars().count() <= 80);
+        }
+        assert!(output[10].contains('┬'));
+        assert!(output[11].contains("Mon 28"));
+        assert!(output[11].contains("Tue 01"));
+
+        let output = chart.with_lang(Language::De).render(&data);
+        assert!(output[0].starts_with("\nVorhersagediagramm (°C, Niederschlag mm/3h)"));
+        assert!(output[11].contains("Mo 28"));
+
+        let output = data.get_chart(40, Units::Imperial.into(), Language::En);
+        assert!(output[1].contains("°F┤"));
+        assert!(
+            output
+                .iter()
+                .skip(1)
+                .all(|l| l.trim_end().chars().count() <= 40)
+        );
+
+        let buf = include_str!("../tests/forecast_full.json");
+        let data: WeatherForecast = serde_json::from_str(buf)?;
+        let output = data.get_chart(40, Units::Metric.into(), Language::En);
+        let precip = &output[9];
+        assert!(precip.starts_with("   0.87mm┤"));
+        assert!(precip.contains('█'));
+        assert!(precip.contains('▄'));
+
+        let empty = WeatherForecast::default();
+        assert_eq!(
+            empty
+                .get_chart(80, Units::Metric.into(), Language::En)
+                .len(),
+            2
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use nutype::nutype;
+
+use crate::Error;
+
+/// Concentration of a pollutant in μg/m³
+#[nutype(
+    validate(greater_or_equal = 0.0),
+    derive(
+        Display,
+        TryFrom,
+        AsRef,
+        Serialize,
+        Deserialize,
+        Copy,
+        Clone,
+        PartialEq,
+        Debug,
+        Into,
+        PartialOrd,
+    )
+)]
+pub struct Concentration(f64);
+
+impl Default for Concentration {
+    fn default() -> Self {
+        Self::try_new(0.0).unwrap()
+    }
+}
+
+impl Concentration {
+    /// # Errors
+    ///
+    /// Will return error if input is less than zero
+    pub fn from_micrograms_per_cubic_meter(c: f64) -> Result<Self, Error> {
+        Self::try_new(c).map_err(Into::into)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn micrograms_per_cubic_meter(self) -> f64 {
+        self.into_inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, concentration::Concentration};
+
+    #[test]
+    fn test_concentration() -> Result<(), Error> {
+        let c = Concentration::from_micrograms_per_cubic_meter(11.52)?;
+        assert_eq!(c.micrograms_per_cubic_meter(), 11.52);
+
+        let c = Concentration::from_micrograms_per_cubic_meter(-1.0);
+        assert_eq!(
+            &format!("{c:?}"),
+            "Err(ConcentrationError(GreaterOrEqualViolated))"
+        );
+        Ok(())
+    }
+}
@@ -1,17 +1,58 @@
 use parking_lot::{Mutex, MutexGuard};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::{
-    env::{remove_var, set_var, var_os},
+    collections::BTreeMap,
+    env::{remove_var, set_var, var_os, vars_os},
     ffi::{OsStr, OsString},
+    fmt::{self, Write},
+    fs::{create_dir_all, read_to_string, write},
     ops::Deref,
-    path::Path,
+    path::{Path, PathBuf},
     sync::{Arc, LazyLock},
 };
+use toml::Table;
 
-use crate::{ApiStringType, Error, StringType, latitude::Latitude, longitude::Longitude};
+use crate::{
+    ApiStringType, Error, StringType, format_string,
+    language::Language,
+    latitude::Latitude,
+    location_profile::LocationProfile,
+    longitude::Longitude,
+    unit_system::{PrecipitationUnit, PressureUnit, SpeedUnit, TemperatureUnit, Units},
+    weather_forecast::DayBoundary,
+    weather_icon::IconStyle,
+};
+
+/// Where the effective value of a configuration variable comes from, in
+/// order of increasing precedence
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigSource {
+    /// Built in default
+    #[default]
+    Default,
+    /// `.env` or `config.env` file
+    Dotenv,
+    /// `config.toml` file
+    Toml,
+    /// Environment variable
+    Env,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::Dotenv => write!(f, "dotenv"),
+            Self::Toml => write!(f, "toml"),
+            Self::Env => write!(f, "env"),
+        }
+    }
+}
 
 /// Configuration data
-#[derive(Default, Debug, Deserialize, PartialEq, Eq)]
+#[derive(Default, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ConfigInner {
     /// openweathermap.org api key
     pub api_key: Option<ApiStringType>,
@@ -24,6 +65,12 @@ pub struct ConfigInner {
     /// Geo Api path (default is `geo/1.0/`)
     #[serde(default = "default_geo_path")]
     pub geo_path: StringType,
+    /// One Call Api path (default is `data/3.0/`)
+    #[serde(default = "default_one_call_path")]
+    pub one_call_path: StringType,
+    /// optional full base url including scheme, host, port and path prefix
+    /// (e.g. `http://localhost:8080/owm/`), overrides `api_endpoint`
+    pub api_base_url: Option<StringType>,
     /// optional default zipcode
     pub zipcode: Option<u64>,
     /// optional default country code
@@ -34,6 +81,296 @@ pub struct ConfigInner {
     pub lat: Option<Latitude>,
     /// optional default longitude
     pub lon: Option<Longitude>,
+    /// optional cache directory (default is `${CACHE_DIR}/weather_util/`)
+    pub cache_dir: Option<PathBuf>,
+    /// optional ttl in seconds for cached current weather (default is 600)
+    pub cache_ttl_weather: Option<u64>,
+    /// optional ttl in seconds for cached forecasts (default is 3600)
+    pub cache_ttl_forecast: Option<u64>,
+    /// optional ttl in seconds for cached geocoding results (default is 30
+    /// days)
+    pub cache_ttl_geo: Option<u64>,
+    /// optional maximum number of attempts for each api request (default is
+    /// 3)
+    pub retry_max_attempts: Option<usize>,
+    /// optional delay in milliseconds before the first retry, doubled on each
+    /// subsequent retry (default is 500)
+    pub retry_base_delay_ms: Option<u64>,
+    /// optional randomization of retry delays (default is true)
+    pub retry_jitter: Option<bool>,
+    /// optional comma separated http statuses which are retried (default is
+    /// `429,500,502,503,504`)
+    pub retry_statuses: Option<Vec<u16>>,
+    /// optional start of forecast days, `midnight` (default) or
+    /// `meteorological` (6am)
+    pub day_boundary: Option<DayBoundary>,
+    /// optional weather icons in text output, one of `emoji`, `nerd`, `ascii`
+    /// or `none` (default is `none`)
+    pub icons: Option<IconStyle>,
+    /// optional language of condition descriptions and text output, one of
+    /// `en` (default), `de`, `fr` or `es`. Named `weather_lang` because `LANG`
+    /// is set by the system locale
+    pub weather_lang: Option<Language>,
+    /// optional units used in text output, one of `metric`, `imperial`,
+    /// `standard` or `mixed` (default is `mixed`)
+    pub units: Option<Units>,
+    /// optional temperature unit, overrides `units`
+    pub temperature_unit: Option<TemperatureUnit>,
+    /// optional wind speed unit, overrides `units`
+    pub speed_unit: Option<SpeedUnit>,
+    /// optional rain and snow unit, overrides `units`
+    pub precipitation_unit: Option<PrecipitationUnit>,
+    /// optional pressure unit, overrides `units`
+    pub pressure_unit: Option<PressureUnit>,
+    /// named `--format` templates, read from `WEATHER_TEMPLATE_<NAME>`
+    /// variables (e.g. `WEATHER_TEMPLATE_TMUX` is available as `--format
+    /// tmux`)
+    #[serde(skip)]
+    pub templates: BTreeMap<StringType, StringType>,
+    /// named locations for `--location` and `--all-locations`, read from
+    /// `[locations.<name>]` tables of config.toml and `LOCATION_<NAME>`
+    /// variables (e.g. `LOCATION_NYC_OFFICE` is available as `--location
+    /// nyc-office`)
+    #[serde(skip)]
+    pub locations: BTreeMap<StringType, LocationProfile>,
+    /// source of each variable that is not a built in default, keyed by the
+    /// lowercase variable name (e.g. `api_key` or `location_nyc_office`)
+    #[serde(skip)]
+    pub sources: BTreeMap<StringType, ConfigSource>,
+    /// config.toml that was read, or that `save_location` creates if it does
+    /// not exist yet
+    #[serde(skip)]
+    pub toml_file: PathBuf,
+}
+
+const TEMPLATE_PREFIX: &str = "WEATHER_TEMPLATE_";
+const LOCATION_PREFIX: &str = "LOCATION_";
+
+/// Sections and keys of `config.toml` with the variable each key sets,
+/// `[output.templates]` tables set `WEATHER_TEMPLATE_<NAME>`
+const TOML_KEYS: &[(&str, &str, &str)] = &[
+    ("api", "key", "API_KEY"),
+    ("api", "endpoint", "API_ENDPOINT"),
+    ("api", "path", "API_PATH"),
+    ("api", "geo_path", "GEO_PATH"),
+    ("api", "one_call_path", "ONE_CALL_PATH"),
+    ("api", "base_url", "API_BASE_URL"),
+    ("api", "retry_max_attempts", "RETRY_MAX_ATTEMPTS"),
+    ("api", "retry_base_delay_ms", "RETRY_BASE_DELAY_MS"),
+    ("api", "retry_jitter", "RETRY_JITTER"),
+    ("api", "retry_statuses", "RETRY_STATUSES"),
+    ("defaults", "zipcode", "ZIPCODE"),
+    ("defaults", "country_code", "COUNTRY_CODE"),
+    ("defaults", "city_name", "CITY_NAME"),
+    ("defaults", "lat", "LAT"),
+    ("defaults", "lon", "LON"),
+    ("defaults", "units", "UNITS"),
+    ("defaults", "temperature_unit", "TEMPERATURE_UNIT"),
+    ("defaults", "speed_unit", "SPEED_UNIT"),
+    ("defaults", "precipitation_unit", "PRECIPITATION_UNIT"),
+    ("defaults", "pressure_unit", "PRESSURE_UNIT"),
+    ("defaults", "lang", "WEATHER_LANG"),
+    ("defaults", "day_boundary", "DAY_BOUNDARY"),
+    ("cache", "dir", "CACHE_DIR"),
+    ("cache", "ttl_weather", "CACHE_TTL_WEATHER"),
+    ("cache", "ttl_forecast", "CACHE_TTL_FORECAST"),
+    ("cache", "ttl_geo", "CACHE_TTL_GEO"),
+    ("output", "icons", "ICONS"),
+];
+
+/// Variables whose values are redacted by `Config::show`
+const SECRET_VARIABLES: &[&str] = &["API_KEY"];
+
+const REDACTED: &str = "********";
+
+fn is_config_variable(key: &str) -> bool {
+    key.starts_with(TEMPLATE_PREFIX)
+        || key.starts_with(LOCATION_PREFIX)
+        || TOML_KEYS.iter().any(|(_, _, var)| *var == key)
+}
+
+/// Environment variables with utf-8 names and values
+fn get_env_vars() -> impl Iterator<Item = (String, String)> {
+    vars_os().filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+}
+
+fn toml_value_to_string(value: &toml::Value) -> Result<String, Error> {
+    match value {
+        toml::Value::String(s) => Ok(s.clone()),
+        toml::Value::Integer(i) => Ok(i.to_string()),
+        toml::Value::Float(f) => Ok(f.to_string()),
+        toml::Value::Boolean(b) => Ok(b.to_string()),
+        toml::Value::Array(values) => Ok(values
+            .iter()
+            .map(|v| match v {
+                toml::Value::Array(_) | toml::Value::Table(_) => Err(Error::InvalidInputError(
+                    format_string!("expected array of strings, numbers or booleans, got {value}"),
+                )),
+                v => toml_value_to_string(v),
+            })
+            .collect::<Result<Vec<_>, Error>>()?
+            .join(",")),
+        _ => Err(Error::InvalidInputError(format_string!(
+            "expected string, number, boolean or array, got {value}"
+        ))),
+    }
+}
+
+/// Contents of `config.toml`
+#[derive(Default)]
+struct TomlFile {
+    /// variables set by the keys of the file
+    vars: Vec<(String, String)>,
+    /// named locations of the `[locations.<name>]` tables
+    locations: BTreeMap<StringType, LocationProfile>,
+}
+
+/// Flatten `config.toml` into the variables its keys set, the
+/// `[locations.<name>]` tables are deserialized into named locations
+fn read_toml_file(path: &Path) -> Result<TomlFile, Error> {
+    let table: Table = read_to_string(path)?.parse()?;
+    let mut vars = Vec::new();
+    let mut locations = BTreeMap::new();
+    for (section, entries) in &table {
+        let toml::Value::Table(entries) = entries else {
+            return Err(Error::InvalidInputError(format_string!(
+                "expected [{section}] to be a table"
+            )));
+        };
+        for (key, value) in entries {
+            match (section.as_str(), key.as_str(), value) {
+                ("locations", name, location @ toml::Value::Table(_)) => {
+                    let name: StringType = name.to_lowercase().replace('_', "-").into();
+                    let profile: LocationProfile = location
+                        .clone()
+                        .try_into()
+                        .map_err(|e| invalid_location(&name, e))?;
+                    profile
+                        .get_location()
+                        .map_err(|e| invalid_location(&name, e))?;
+                    locations.insert(name, profile);
+                }
+                ("output", "templates", toml::Value::Table(templates)) => {
+                    for (name, template) in templates {
+                        let name = name.to_uppercase();
+                        let template = toml_value_to_string(template)?;
+                        vars.push((format!("{TEMPLATE_PREFIX}{name}"), template));
+                    }
+                }
+                (section, key, value) => {
+                    let (_, _, var) = TOML_KEYS
+                        .iter()
+                        .find(|(s, k, _)| *s == section && *k == key)
+                        .ok_or_else(|| {
+                            Error::InvalidInputError(format_string!(
+                                "unknown key {key} in [{section}] of {}",
+                                path.display()
+                            ))
+                        })?;
+                    vars.push(((*var).into(), toml_value_to_string(value)?));
+                }
+            }
+        }
+    }
+    Ok(TomlFile { vars, locations })
+}
+
+fn invalid_location(name: &str, e: impl fmt::Display) -> Error {
+    Error::InvalidInputError(format_string!("invalid location {name}: {e}"))
+}
+
+/// Variable of the named location `name`, e.g. `LOCATION_NYC_OFFICE` for
+/// `nyc-office`
+fn location_variable(name: &str) -> String {
+    format!("{LOCATION_PREFIX}{}", name.to_uppercase().replace('-', "_"))
+}
+
+fn get_templates(vars: &BTreeMap<String, String>) -> BTreeMap<StringType, StringType> {
+    vars.iter()
+        .filter_map(|(key, value)| {
+            let name = key.strip_prefix(TEMPLATE_PREFIX)?;
+            Some((name.to_lowercase().into(), value.into()))
+        })
+        .collect()
+}
+
+fn get_locations(
+    vars: &BTreeMap<String, String>,
+) -> Result<BTreeMap<StringType, LocationProfile>, Error> {
+    vars.iter()
+        .filter_map(|(key, value)| {
+            let name = key.strip_prefix(LOCATION_PREFIX)?;
+            let name: StringType = name.to_lowercase().replace('_', "-").into();
+            let profile = value.parse().map_err(|e| invalid_location(&name, e));
+            Some(profile.map(|profile| (name, profile)))
+        })
+        .collect()
+}
+
+/// Append `profile` to the config.toml at `path` as `[locations.<name>]`
+/// preceded by a `description` comment, the file is created if it does not
+/// exist
+/// # Errors
+///
+/// Will return Error if `name` is not a valid location name, if the location
+/// already exists in the file or if the file cannot be read or written
+pub fn save_location(
+    path: &Path,
+    name: &str,
+    profile: &LocationProfile,
+    description: &str,
+) -> Result<(), Error> {
+    let valid_name = name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if name.is_empty() || !valid_name {
+        return Err(Error::InvalidInputError(format_string!(
+            "invalid location name {name}, use lowercase letters, digits and -"
+        )));
+    }
+    let mut output = if path.exists() {
+        read_to_string(path)?
+    } else {
+        String::new()
+    };
+    let table: Table = output.parse()?;
+    if table.get("locations").and_then(|l| l.get(name)).is_some() {
+        return Err(Error::InvalidInputError(format_string!(
+            "location {name} already exists in {}",
+            path.display()
+        )));
+    }
+    if !output.is_empty() {
+        if !output.ends_with('\n') {
+            output.push('\n');
+        }
+        output.push('\n');
+    }
+    let mut table = Table::try_from(profile)?;
+    // keep the precision of the `Display` impls rather than the full precision
+    // of the fixed point angle
+    if let Some(lat) = profile.lat {
+        table.insert("lat".into(), toml::Value::Float(lat.to_string().parse()?));
+    }
+    if let Some(lon) = profile.lon {
+        table.insert("lon".into(), toml::Value::Float(lon.to_string().parse()?));
+    }
+    writeln!(output, "# {description}")?;
+    writeln!(output, "[locations.{name}]")?;
+    output.push_str(&toml::to_string(&table)?);
+    if let Some(directory) = path.parent() {
+        create_dir_all(directory)?;
+    }
+    write(path, output)?;
+    Ok(())
+}
+
+/// `value` as it would be written in `config.toml`
+fn format_toml_value(value: &Value) -> StringType {
+    match value {
+        Value::String(s) => toml::Value::String(s.clone()).to_string().into(),
+        value => format_string!("{value}"),
+    }
 }
 
 fn default_api_endpoint() -> StringType {
@@ -48,6 +385,10 @@ fn default_geo_path() -> StringType {
     "geo/1.0/".into()
 }
 
+fn default_one_call_path() -> StringType {
+    "data/3.0/".into()
+}
+
 /// Configuration struct
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct Config(Arc<ConfigInner>);
@@ -58,7 +399,8 @@ impl Config {
         Self::default()
     }
 
-    /// Pull in configuration data using `[dotenv](https://crates.io/dotenv)`.
+    /// Pull in configuration data using `[dotenv](https://crates.io/dotenv)`
+    /// and `config.toml`.
     ///
     /// If a .env file exists in the current directory, pull in any ENV
     /// variables in it.
@@ -70,6 +412,36 @@ impl Config {
     /// Config files should have lines of the following form:
     /// `API_KEY=api_key_value`
     ///
+    /// Finally, if a file named config.toml exists in the current directory or
+    /// at `${HOME}/.config/weather_util/config.toml`, read it. `config_path`
+    /// may also point directly at a `.toml` file. It has `[api]`,
+    /// `[defaults]`, `[cache]` and `[output]` sections, see `TOML_KEYS`,
+    /// `[output.templates]` and `[locations.<name>]` tables, e.g.
+    ///
+    /// ```toml
+    /// [api]
+    /// key = "api_key_value"
+    ///
+    /// [defaults]
+    /// zipcode = 11106
+    /// units = "imperial"
+    ///
+    /// [locations.berlin]
+    /// city_name = "Berlin,DE"
+    /// units = "metric"
+    /// lang = "de"
+    /// ```
+    ///
+    /// Values are taken from, in order of precedence, environment variables,
+    /// config.toml, .env / config.env files and built in defaults. Command
+    /// line options take precedence over all of them.
+    ///
+    /// Lines of the form `WEATHER_TEMPLATE_TMUX={temp_c:.0}°C` define named
+    /// `--format` templates.
+    ///
+    /// Lines of the form `LOCATION_BERLIN="city_name=Berlin,DE;units=metric"`
+    /// define named locations, see `LocationProfile`.
+    ///
     /// # Example
     ///
     /// ```
@@ -93,9 +465,13 @@ impl Config {
     /// ```
     /// # Errors
     ///
-    /// Will return Error if unable to deserialize env variables
+    /// Will return Error if unable to deserialize env variables, if
+    /// config.toml is invalid or if a named location is invalid
     pub fn init_config(config_path: Option<&Path>) -> Result<Self, Error> {
-        let fname = config_path.unwrap_or_else(|| Path::new("config.env"));
+        let is_toml = config_path.is_some_and(|p| p.extension() == Some(OsStr::new("toml")));
+        let fname = config_path
+            .filter(|_| !is_toml)
+            .unwrap_or_else(|| Path::new("config.env"));
         let config_dir = dirs::config_dir().unwrap_or_else(|| "./".into());
         let default_fname = config_dir.join("weather_util").join("config.env");
 
@@ -105,16 +481,122 @@ impl Config {
             &default_fname
         };
 
+        let toml_fname = config_path
+            .filter(|_| is_toml)
+            .unwrap_or_else(|| Path::new("config.toml"));
+        let default_toml_fname = config_dir.join("weather_util").join("config.toml");
+        let toml_file = if toml_fname.exists() {
+            toml_fname
+        } else {
+            &default_toml_fname
+        };
+
+        // dotenv files do not override variables which are already set, so
+        // anything that appears after loading them comes from a dotenv file
+        let env_vars: Vec<_> = get_env_vars().collect();
+
         dotenvy::dotenv().ok();
 
         if env_file.exists() {
             dotenvy::from_path(env_file).ok();
         }
 
-        let conf: ConfigInner = envy::from_env()?;
+        let mut sources: BTreeMap<String, ConfigSource> = get_env_vars()
+            .map(|(key, _)| (key, ConfigSource::Dotenv))
+            .collect();
+        let mut vars: BTreeMap<String, String> = get_env_vars().collect();
+        let toml = if toml_file.exists() {
+            read_toml_file(toml_file)?
+        } else {
+            TomlFile::default()
+        };
+        for (key, value) in toml.vars {
+            sources.insert(key.clone(), ConfigSource::Toml);
+            vars.insert(key, value);
+        }
+        // a toml location replaces a dotenv variable of the same name, an
+        // environment variable replaces both
+        for name in toml.locations.keys() {
+            let var = location_variable(name);
+            vars.remove(&var);
+            sources.insert(var, ConfigSource::Toml);
+        }
+        for (key, value) in env_vars {
+            sources.insert(key.clone(), ConfigSource::Env);
+            vars.insert(key, value);
+        }
+
+        let mut conf: ConfigInner = envy::from_iter(vars.clone())?;
+        conf.templates = get_templates(&vars);
+        conf.locations = toml.locations;
+        conf.locations.extend(get_locations(&vars)?);
+        conf.toml_file = toml_file.to_path_buf();
+        conf.sources = sources
+            .into_iter()
+            .filter(|(key, _)| is_config_variable(key))
+            .map(|(key, source)| (key.to_lowercase().into(), source))
+            .collect();
 
         Ok(Self(Arc::new(conf)))
     }
+
+    /// Source of the effective value of `variable`, e.g. `API_KEY`
+    #[must_use]
+    pub fn get_source(&self, variable: &str) -> ConfigSource {
+        self.sources
+            .get(variable.to_lowercase().as_str())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Effective configuration in the layout of `config.toml`, with secrets
+    /// redacted and the source of each value as a comment
+    /// # Errors
+    ///
+    /// Will return Error if the configuration cannot be serialized
+    pub fn show(&self) -> Result<StringType, Error> {
+        let Value::Object(values) = serde_json::to_value(&*self.0)? else {
+            return Err(Error::InvalidValue("expected config object".into()));
+        };
+        let mut output = StringType::new();
+        let mut current_section = "";
+        for (section, key, var) in TOML_KEYS {
+            let Some(value) = values.get(&var.to_lowercase()).filter(|v| !v.is_null()) else {
+                continue;
+            };
+            if *section != current_section {
+                if !current_section.is_empty() {
+                    output.push('\n');
+                }
+                writeln!(output, "[{section}]")?;
+                current_section = section;
+            }
+            let value = if SECRET_VARIABLES.contains(var) {
+                format_toml_value(&Value::String(REDACTED.into()))
+            } else {
+                format_toml_value(value)
+            };
+            writeln!(output, "{key} = {value}  # {}", self.get_source(var))?;
+        }
+        if !self.templates.is_empty() {
+            output.push_str("\n[output.templates]\n");
+            for (name, template) in &self.templates {
+                let var = format_string!("{TEMPLATE_PREFIX}{name}");
+                let value = format_toml_value(&Value::String(template.to_string()));
+                writeln!(output, "{name} = {value}  # {}", self.get_source(&var))?;
+            }
+        }
+        for (name, location) in &self.locations {
+            let var = location_variable(name);
+            writeln!(output, "\n[locations.{name}]  # {}", self.get_source(&var))?;
+            if let Value::Object(fields) = serde_json::to_value(location)? {
+                for (key, value) in fields.iter().filter(|(_, v)| !v.is_null()) {
+                    writeln!(output, "{key} = {}", format_toml_value(value))?;
+                }
+            }
+        }
+        Ok(output)
+    }
 }
 
 impl Deref for Config {
@@ -170,11 +652,15 @@ mod tests {
         env::{remove_var, set_var},
         fs::write,
     };
-    use tempfile::NamedTempFile;
+    use tempfile::{Builder, NamedTempFile};
 
     use crate::{
-        Error,
-        config::{Config, TestEnvs},
+        Error, StringType,
+        config::{Config, ConfigSource, TestEnvs},
+        language::Language,
+        unit_system::{SpeedUnit, Units},
+        weather_forecast::DayBoundary,
+        weather_icon::IconStyle,
     };
 
     #[test]
@@ -208,6 +694,146 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_config_units() -> Result<(), Error> {
+        let _env = TestEnvs::new(&[
+            "UNITS",
+            "SPEED_UNIT",
+            "DAY_BOUNDARY",
+            "ICONS",
+            "TEMPLATE_TMUX",
+            "WEATHER_TEMPLATE_TMUX",
+            "WEATHER_LANG",
+            "LOCATION_NYC_OFFICE",
+        ]);
+        unsafe {
+            set_var("LOCATION_NYC_OFFICE", "zipcode=10001;lang=es");
+            set_var("WEATHER_LANG", "fr");
+            set_var("TEMPLATE_TMUX", "not a weather template");
+            set_var("WEATHER_TEMPLATE_TMUX", "{temp_c:.0}°C {icon}");
+            set_var("ICONS", "ascii");
+            set_var("UNITS", "metric");
+            set_var("SPEED_UNIT", "mps");
+            set_var("DAY_BOUNDARY", "meteorological");
+        }
+        let conf = Config::init_config(None)?;
+        drop(_env);
+
+        assert_eq!(conf.units, Some(Units::Metric));
+        assert_eq!(conf.speed_unit, Some(SpeedUnit::Mps));
+        assert_eq!(conf.temperature_unit, None);
+        assert_eq!(conf.day_boundary, Some(DayBoundary::Meteorological));
+        assert_eq!(conf.icons, Some(IconStyle::Ascii));
+        assert_eq!(conf.weather_lang, Some(Language::Fr));
+        assert_eq!(
+            conf.templates.get("tmux").map(StringType::as_str),
+            Some("{temp_c:.0}°C {icon}")
+        );
+        assert_eq!(conf.templates.len(), 1);
+        let location = conf.locations.get("nyc-office").unwrap();
+        assert_eq!(location.zipcode, Some(10001));
+        assert_eq!(location.lang, Some(Language::Es));
+
+        let _env = TestEnvs::new(&["LOCATION_NOWHERE"]);
+        unsafe {
+            set_var("LOCATION_NOWHERE", "units=metric");
+        }
+        assert!(Config::init_config(None).is_err());
+        drop(_env);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_toml() -> Result<(), Error> {
+        let _env = TestEnvs::new(&[
+            "API_KEY",
+            "API_ENDPOINT",
+            "ZIPCODE",
+            "UNITS",
+            "WEATHER_LANG",
+            "ICONS",
+            "CACHE_TTL_WEATHER",
+            "RETRY_MAX_ATTEMPTS",
+            "RETRY_STATUSES",
+        ]);
+        unsafe {
+            remove_var("API_KEY");
+            remove_var("API_ENDPOINT");
+            remove_var("ZIPCODE");
+            remove_var("WEATHER_LANG");
+            remove_var("ICONS");
+            remove_var("CACHE_TTL_WEATHER");
+            remove_var("RETRY_MAX_ATTEMPTS");
+            remove_var("RETRY_STATUSES");
+            set_var("UNITS", "standard");
+        }
+        let config_file = Builder::new().suffix(".toml").tempfile()?;
+        write(config_file.path(), include_bytes!("../tests/config.toml"))?;
+        let conf = Config::init_config(Some(config_file.path()))?;
+
+        let bad_file = Builder::new().suffix(".toml").tempfile()?;
+        write(bad_file.path(), "[api]\nkey = \"abc\"\ncolor = \"blue\"\n")?;
+        assert!(Config::init_config(Some(bad_file.path())).is_err());
+        write(bad_file.path(), "[locations.lab]\nzipcode = \"abc\"\n")?;
+        assert!(Config::init_config(Some(bad_file.path())).is_err());
+
+        // values are not re-parsed as `;` separated `key=value` pairs
+        let lab_file = Builder::new().suffix(".toml").tempfile()?;
+        write(
+            lab_file.path(),
+            "[locations.lab]\ncity_name = \"Astoria;units=metric\"\nlat = 40.76\n",
+        )?;
+        let lab = Config::init_config(Some(lab_file.path()))?;
+        drop(_env);
+        let location = &lab.locations["lab"];
+        assert_eq!(
+            location.city_name.as_ref().map(StringType::as_str),
+            Some("Astoria;units=metric")
+        );
+        assert_eq!(location.units, None);
+        assert_eq!(location.lat, Some(40.76.try_into()?));
+        assert_eq!(lab.get_source("LOCATION_LAB"), ConfigSource::Toml);
+
+        assert_eq!(
+            conf.api_key.as_ref().map(|k| k.as_str()),
+            Some("fb2380d74189c9983ea52f55914da824")
+        );
+        assert_eq!(&conf.api_endpoint, "test.local");
+        assert_eq!(&conf.api_path, "data/2.5/");
+        assert_eq!(conf.zipcode, Some(11106));
+        assert_eq!(conf.units, Some(Units::Standard));
+        assert_eq!(conf.weather_lang, Some(Language::De));
+        assert_eq!(conf.icons, Some(IconStyle::Ascii));
+        assert_eq!(conf.cache_ttl_weather, Some(60));
+        assert_eq!(conf.retry_max_attempts, Some(5));
+        assert_eq!(conf.retry_statuses, Some(vec![429, 503]));
+        assert_eq!(conf.retry_jitter, None);
+        assert_eq!(conf.locations.len(), 2);
+        assert_eq!(conf.locations["nyc-office"].units, Some(Units::Metric));
+        assert!(conf.templates.contains_key("tmux"));
+
+        assert_eq!(conf.get_source("UNITS"), ConfigSource::Env);
+        assert_eq!(conf.get_source("ZIPCODE"), ConfigSource::Toml);
+        assert_eq!(conf.get_source("API_PATH"), ConfigSource::Default);
+        assert_eq!(conf.get_source("LOCATION_NYC_OFFICE"), ConfigSource::Toml);
+
+        let output = conf.show()?;
+        assert!(!output.contains("fb2380d74189c9983ea52f55914da824"));
+        assert!(output.starts_with("[api]\nkey = \"********\"  # toml\n"));
+        assert!(output.contains("path = \"data/2.5/\"  # default\n"));
+        assert!(output.contains("retry_statuses = [429,503]  # toml\n"));
+        assert!(output.contains("\n[defaults]\nzipcode = 11106  # toml\n"));
+        assert!(output.contains("units = \"standard\"  # env\n"));
+        assert!(output.contains("lang = \"de\"  # toml\n"));
+        assert!(output.contains("\n[output.templates]\ntmux = \"{temp_c:.0}°C {icon}\"  # toml\n"));
+        assert!(
+            output.contains(
+                "\n[locations.nyc-office]  # toml\nunits = \"metric\"\nzipcode = 10001\n"
+            )
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_config_file() -> Result<(), Error> {
         let _env = TestEnvs::new(&["API_KEY", "API_ENDPOINT", "ZIPCODE", "API_PATH"]);
@@ -0,0 +1,337 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+use crate::{
+    Error, StringType,
+    absolute_humidity::AbsoluteHumidity,
+    format_string,
+    humidity::Humidity,
+    language::{Language, Message},
+    speed::Speed,
+    temperature::Temperature,
+    unit_system::UnitSystem,
+};
+
+// Magnus coefficients (Sonntag 1990), valid between -45 °C and 60 °C
+const MAGNUS_B: f64 = 17.62;
+const MAGNUS_C: f64 = 243.12;
+
+fn relative_humidity(rh: Humidity) -> f64 {
+    rh.into_inner() as f64
+}
+
+/// Dew point using the Magnus formula, accurate to within 0.35 °C for
+/// temperatures between -45 °C and 60 °C.
+/// ```
+/// use weather_util_rust::{derived::dew_point, humidity::Humidity, temperature::Temperature};
+/// # use anyhow::Error;
+/// # fn main() -> Result<(), Error> {
+/// let t = Temperature::from_celcius(20.0)?;
+/// let td = dew_point(t, Humidity::try_new(50)?)?;
+/// assert!((td.celcius() - 9.26).abs() < 0.01);
+/// # Ok(())
+/// # }
+/// ```
+/// # Errors
+///
+/// Will return error if relative humidity is zero
+pub fn dew_point(t: Temperature, rh: Humidity) -> Result<Temperature, Error> {
+    if rh.into_inner() == 0 {
+        return Err(Error::InvalidValue(
+            "Dew point undefined at 0% humidity".into(),
+        ));
+    }
+    let t = t.celcius();
+    let gamma = (relative_humidity(rh) / 100.0).ln() + MAGNUS_B * t / (MAGNUS_C + t);
+    Temperature::from_celcius(MAGNUS_C * gamma / (MAGNUS_B - gamma))
+}
+
+/// Heat index using the NWS algorithm: Steadman's simple formula below
+/// 80 °F, otherwise the Rothfusz regression with adjustments for low and high
+/// humidity.  The regression is only meaningful above 80 °F and 40% relative
+/// humidity, its error is ±1.3 °F.
+/// # Errors
+///
+/// Will return error if the result is not a valid temperature
+pub fn heat_index(t: Temperature, rh: Humidity) -> Result<Temperature, Error> {
+    let t = t.fahrenheit();
+    let rh = relative_humidity(rh);
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    if (simple + t) / 2.0 < 80.0 {
+        return Temperature::from_fahrenheit(simple);
+    }
+    let mut hi = -42.379 + 2.049_015_23 * t + 10.143_331_27 * rh
+        - 0.224_755_41 * t * rh
+        - 0.006_837_83 * t * t
+        - 0.054_817_17 * rh * rh
+        + 0.001_228_74 * t * t * rh
+        + 0.000_852_82 * t * rh * rh
+        - 0.000_001_99 * t * t * rh * rh;
+    if rh < 13.0 && (80.0..=112.0).contains(&t) {
+        hi -= ((13.0 - rh) / 4.0) * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+    } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+        hi += ((rh - 85.0) / 10.0) * ((87.0 - t) / 5.0);
+    }
+    Temperature::from_fahrenheit(hi)
+}
+
+/// Wind chill using the 2001 NWS formula, only defined for temperatures at or
+/// below 50 °F and wind speeds of at least 3 mph, returns `None` otherwise.
+/// ```
+/// use weather_util_rust::{derived::wind_chill, speed::Speed, temperature::Temperature};
+/// # use anyhow::Error;
+/// # fn main() -> Result<(), Error> {
+/// let t = Temperature::from_fahrenheit(0.0)?;
+/// let wc = wind_chill(t, Speed::from_mph(15.0)?).unwrap();
+/// assert!((wc.fahrenheit() + 19.4).abs() < 0.1);
+/// assert_eq!(wind_chill(Temperature::from_fahrenheit(60.0)?, Speed::from_mph(15.0)?), None);
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+pub fn wind_chill(t: Temperature, wind: Speed) -> Option<Temperature> {
+    let t = t.fahrenheit();
+    let v = wind.mph();
+    if t > 50.0 || v < 3.0 {
+        return None;
+    }
+    let v = v.powf(0.16);
+    Temperature::from_fahrenheit(35.74 + 0.6215 * t - 35.75 * v + 0.4275 * t * v).ok()
+}
+
+/// Humidex as defined by Environment Canada, usually only reported when the
+/// temperature is at least 20 °C, the value is expressed in °C.
+/// # Errors
+///
+/// Will return error if relative humidity is zero
+pub fn humidex(t: Temperature, rh: Humidity) -> Result<Temperature, Error> {
+    let td = dew_point(t, rh)?;
+    let e = 6.11 * (5417.7530 * (1.0 / 273.16 - 1.0 / td.kelvin())).exp();
+    Temperature::from_celcius(t.celcius() + 0.5555 * (e - 10.0))
+}
+
+/// Apparent temperature as used by the Australian Bureau of Meteorology
+/// (Steadman 1994, without solar radiation), valid for shade temperatures
+/// between -40 °C and 50 °C.
+/// # Errors
+///
+/// Will return error if the result is not a valid temperature
+pub fn apparent_temperature(
+    t: Temperature,
+    rh: Humidity,
+    wind: Speed,
+) -> Result<Temperature, Error> {
+    let t = t.celcius();
+    let e = relative_humidity(rh) / 100.0 * 6.105 * (17.27 * t / (237.7 + t)).exp();
+    Temperature::from_celcius(t + 0.33 * e - 0.70 * wind.mps() - 4.00)
+}
+
+/// Absolute humidity from temperature and relative humidity, accurate to
+/// within 0.1% for temperatures between -30 °C and 35 °C.
+/// # Errors
+///
+/// Will return error if the result is not a valid absolute humidity
+pub fn absolute_humidity(t: Temperature, rh: Humidity) -> Result<AbsoluteHumidity, Error> {
+    let t = t.celcius();
+    let saturation = 6.112 * (17.67 * t / (t + 243.5)).exp();
+    AbsoluteHumidity::from_grams_per_cubic_meter(
+        saturation * relative_humidity(rh) * 2.1674 / (273.15 + t),
+    )
+}
+
+/// Quantities derived from temperature, relative humidity and (optionally)
+/// wind speed, values which are undefined or not meaningful for the
+/// conditions are `None`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct DerivedConditions {
+    pub dew_point: Option<Temperature>,
+    /// Only set at or above 80 °F
+    pub heat_index: Option<Temperature>,
+    pub wind_chill: Option<Temperature>,
+    /// Only set at or above 20 °C
+    pub humidex: Option<Temperature>,
+    pub apparent_temperature: Option<Temperature>,
+    pub absolute_humidity: Option<AbsoluteHumidity>,
+}
+
+impl DerivedConditions {
+    #[must_use]
+    pub fn new(t: Temperature, rh: Humidity, wind: Option<Speed>) -> Self {
+        Self {
+            dew_point: dew_point(t, rh).ok(),
+            heat_index: if t.fahrenheit() >= 80.0 {
+                heat_index(t, rh).ok()
+            } else {
+                None
+            },
+            wind_chill: wind.and_then(|w| wind_chill(t, w)),
+            humidex: if t.celcius() >= 20.0 {
+                humidex(t, rh).ok()
+            } else {
+                None
+            },
+            apparent_temperature: wind.and_then(|w| apparent_temperature(t, rh, w).ok()),
+            absolute_humidity: absolute_humidity(t, rh).ok(),
+        }
+    }
+
+    /// One tab indented line per derived quantity which is set
+    #[must_use]
+    pub fn get_conditions(&self, units: UnitSystem, lang: Language) -> StringType {
+        let mut output = StringType::new();
+        for (label, value) in [
+            (Message::DewPoint, self.dew_point),
+            (Message::ApparentTemperature, self.apparent_temperature),
+            (Message::HeatIndex, self.heat_index),
+            (Message::WindChill, self.wind_chill),
+            (Message::Humidex, self.humidex),
+        ] {
+            if let Some(value) = value {
+                let value = units.format_temperature(value, 2);
+                writeln!(
+                    output,
+                    "\t{}: {}",
+                    lang.get(label),
+                    lang.localize_decimal(&value)
+                )
+                .unwrap_or(());
+            }
+        }
+        if let Some(h) = self.absolute_humidity {
+            let value = format_string!("{:0.2} g/m³", h.grams_per_cubic_meter());
+            writeln!(
+                output,
+                "\t{}: {}",
+                lang.get(Message::AbsoluteHumidity),
+                lang.localize_decimal(&value)
+            )
+            .unwrap_or(());
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use crate::{
+        Error,
+        derived::{
+            DerivedConditions, absolute_humidity, apparent_temperature, dew_point, heat_index,
+            humidex, wind_chill,
+        },
+        humidity::Humidity,
+        language::Language,
+        speed::Speed,
+        temperature::Temperature,
+        unit_system::{UnitSystem, Units},
+    };
+
+    #[test]
+    fn test_dew_point() -> Result<(), Error> {
+        let t = Temperature::from_celcius(20.0)?;
+        assert_abs_diff_eq!(
+            dew_point(t, Humidity::try_new(50)?)?.celcius(),
+            9.26,
+            epsilon = 0.01
+        );
+        assert_abs_diff_eq!(
+            dew_point(t, Humidity::try_new(100)?)?.celcius(),
+            20.0,
+            epsilon = 1e-9
+        );
+        assert!(dew_point(t, Humidity::try_new(0)?).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_heat_index() -> Result<(), Error> {
+        // values from the NWS heat index chart
+        for (t, rh, expected) in [(90.0, 70, 106.0), (100.0, 40, 109.0), (86.0, 90, 105.0)] {
+            let hi = heat_index(Temperature::from_fahrenheit(t)?, Humidity::try_new(rh)?)?;
+            assert_abs_diff_eq!(hi.fahrenheit(), expected, epsilon = 1.0);
+        }
+        // low humidity adjustment
+        let hi = heat_index(Temperature::from_fahrenheit(95.0)?, Humidity::try_new(5)?)?;
+        assert_abs_diff_eq!(hi.fahrenheit(), 88.2, epsilon = 0.1);
+        // simple formula below 80 °F
+        let hi = heat_index(Temperature::from_fahrenheit(70.0)?, Humidity::try_new(50)?)?;
+        assert_abs_diff_eq!(hi.fahrenheit(), 69.05, epsilon = 0.01);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wind_chill() -> Result<(), Error> {
+        // values from the NWS wind chill chart
+        for (t, v, expected) in [(0.0, 15.0, -19.0), (30.0, 5.0, 25.0), (-20.0, 30.0, -53.0)] {
+            let wc = wind_chill(Temperature::from_fahrenheit(t)?, Speed::from_mph(v)?).unwrap();
+            assert_abs_diff_eq!(wc.fahrenheit(), expected, epsilon = 0.6);
+        }
+        let t = Temperature::from_fahrenheit(30.0)?;
+        assert_eq!(wind_chill(t, Speed::from_mph(2.0)?), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_humidex() -> Result<(), Error> {
+        // 30 °C with a dew point of 15 °C
+        let t = Temperature::from_celcius(30.0)?;
+        let h = humidex(t, Humidity::try_new(40)?)?;
+        assert_abs_diff_eq!(h.celcius(), 33.8, epsilon = 0.2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_apparent_temperature() -> Result<(), Error> {
+        let t = Temperature::from_celcius(25.0)?;
+        let at = apparent_temperature(t, Humidity::try_new(50)?, Speed::from_mps(2.0)?)?;
+        assert_abs_diff_eq!(at.celcius(), 24.81, epsilon = 0.01);
+        Ok(())
+    }
+
+    #[test]
+    fn test_absolute_humidity() -> Result<(), Error> {
+        let t = Temperature::from_celcius(20.0)?;
+        let h = absolute_humidity(t, Humidity::try_new(50)?)?;
+        assert_abs_diff_eq!(h.grams_per_cubic_meter(), 8.64, epsilon = 0.01);
+        let h = absolute_humidity(t, Humidity::try_new(0)?)?;
+        assert_abs_diff_eq!(h.grams_per_cubic_meter(), 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_derived_conditions() -> Result<(), Error> {
+        let t = Temperature::from_celcius(-5.0)?;
+        let derived =
+            DerivedConditions::new(t, Humidity::try_new(60)?, Some(Speed::from_mps(5.0)?));
+        assert!(derived.dew_point.is_some());
+        assert!(derived.wind_chill.is_some());
+        assert_eq!(derived.heat_index, None);
+        assert_eq!(derived.humidex, None);
+
+        let output = derived.get_conditions(Units::Metric.into(), Language::En);
+        let labels: Vec<_> = output
+            .lines()
+            .map(|l| l.split(':').next().unwrap().trim())
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Dew Point",
+                "Apparent Temperature",
+                "Wind Chill",
+                "Absolute Humidity"
+            ]
+        );
+
+        let t = Temperature::from_celcius(32.0)?;
+        let derived = DerivedConditions::new(t, Humidity::try_new(0)?, None);
+        assert_eq!(derived.dew_point, None);
+        assert_eq!(derived.apparent_temperature, None);
+        assert!(derived.heat_index.is_some());
+        let output = derived.get_conditions(UnitSystem::default(), Language::En);
+        assert!(output.starts_with("\tHeat Index: "));
+        Ok(())
+    }
+}
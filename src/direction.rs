@@ -3,6 +3,27 @@ use serde::{Deserialize, Serialize};
 
 use crate::angle::Angle;
 
+// 32-point compass rose, every other point gives the 16-point rose etc.
+const COMPASS_POINTS: [&str; 32] = [
+    "N", "NbE", "NNE", "NEbN", "NE", "NEbE", "ENE", "EbN", "E", "EbS", "ESE", "SEbE", "SE", "SEbS",
+    "SSE", "SbE", "S", "SbW", "SSW", "SWbS", "SW", "SWbW", "WSW", "WbS", "W", "WbN", "WNW", "NWbW",
+    "NW", "NWbN", "NNW", "NbW",
+];
+
+/// Number of points of the compass rose used by `Direction::compass_point`
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum CompassPoints {
+    /// N, E, S, W
+    Four = 4,
+    /// N, NE, E, ...
+    Eight = 8,
+    /// N, NNE, NE, ENE, E, ...
+    #[default]
+    Sixteen = 16,
+    /// N, NbE, NNE, NEbN, NE, ...
+    ThirtyTwo = 32,
+}
+
 /// Direction in degrees
 #[derive(
     Into,
@@ -37,6 +58,21 @@ impl Direction {
     pub fn from_radian(rad: f64) -> Self {
         Self(Angle::from_radian(rad))
     }
+
+    /// Name of the nearest point of the compass rose
+    /// ```
+    /// use weather_util_rust::direction::{CompassPoints, Direction};
+    /// assert_eq!(Direction::from_deg(290.0).compass_point(CompassPoints::Sixteen), "WNW");
+    /// assert_eq!(Direction::from_deg(290.0).compass_point(CompassPoints::Four), "W");
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn compass_point(self, points: CompassPoints) -> &'static str {
+        let points = points as usize;
+        let width = 360.0 / points as f64;
+        let index = (self.deg().rem_euclid(360.0) / width).round() as usize % points;
+        COMPASS_POINTS[index * (COMPASS_POINTS.len() / points)]
+    }
 }
 
 #[cfg(test)]
@@ -44,7 +80,7 @@ mod tests {
     use approx::assert_abs_diff_eq;
     use std::f64::consts::PI;
 
-    use crate::direction::Direction;
+    use crate::direction::{CompassPoints, Direction};
 
     #[test]
     fn test_direction() {
@@ -75,4 +111,23 @@ mod tests {
             Direction::from_radian(-1.0 * PI / 2.).radian()
         );
     }
+
+    #[test]
+    fn test_compass_point() {
+        for (deg, four, eight, sixteen, thirty_two) in [
+            (0.0, "N", "N", "N", "N"),
+            (10.0, "N", "N", "N", "NbE"),
+            (22.5, "N", "NE", "NNE", "NNE"),
+            (250.0, "W", "W", "WSW", "WSW"),
+            (300.0, "W", "NW", "WNW", "NWbW"),
+            (350.0, "N", "N", "N", "NbW"),
+            (-90.0, "W", "W", "W", "W"),
+        ] {
+            let d = Direction::from_deg(deg);
+            assert_eq!(d.compass_point(CompassPoints::Four), four);
+            assert_eq!(d.compass_point(CompassPoints::Eight), eight);
+            assert_eq!(d.compass_point(CompassPoints::Sixteen), sixteen);
+            assert_eq!(d.compass_point(CompassPoints::ThirtyTwo), thirty_two);
+        }
+    }
 }
@@ -2,12 +2,14 @@ use envy::Error as EnvyError;
 use serde_json::Error as SerdeJsonError;
 use std::{fmt::Error as FmtError, io::Error as IoError, num::ParseFloatError};
 use thiserror::Error;
+use toml::{de::Error as TomlError, ser::Error as TomlSerError};
 use url::ParseError as UrlParseError;
 
 use crate::{
-    distance::DistanceError, humidity::HumidityError, precipitation::PrecipitationError,
-    pressure::PressureError, speed::SpeedError, temperature::TemperatureError,
-    timezone::TimeZoneError,
+    absolute_humidity::AbsoluteHumidityError, api_error::ApiError,
+    concentration::ConcentrationError, distance::DistanceError, humidity::HumidityError,
+    precipitation::PrecipitationError, pressure::PressureError, speed::SpeedError,
+    temperature::TemperatureError, timezone::TimeZoneError,
 };
 
 #[cfg(feature = "cli")]
@@ -26,6 +28,10 @@ pub enum Error {
     ParseFloatError(#[from] ParseFloatError),
     #[error("Environment Parsing Error {0}")]
     EnvyError(#[from] EnvyError),
+    #[error("TOML Parsing Error {0}")]
+    TomlError(#[from] TomlError),
+    #[error("TOML Serialization Error {0}")]
+    TomlSerError(#[from] TomlSerError),
     #[error("URL Parse Error {0}")]
     UrlParseError(#[from] UrlParseError),
     #[error("JSON Serde Error {0}")]
@@ -36,6 +42,10 @@ pub enum Error {
     InvalidValue(StringType),
     #[error("Invalid Input Error {0}")]
     InvalidInputError(StringType),
+    #[error("AbsoluteHumidityError {0}")]
+    AbsoluteHumidityError(#[from] AbsoluteHumidityError),
+    #[error("ConcentrationError {0}")]
+    ConcentrationError(#[from] ConcentrationError),
     #[error("DistanceError {0}")]
     DistanceError(#[from] DistanceError),
     #[error("HumidityError {0}")]
@@ -54,6 +64,26 @@ pub enum Error {
     TemperatureError(#[from] TemperatureError),
     #[error("TimeZoneError {0}")]
     TimeZoneError(#[from] TimeZoneError),
+    #[error("Invalid Api Key: {0}")]
+    InvalidApiKey(StringType),
+    #[error("Location Not Found: {0}")]
+    LocationNotFound(StringType),
+    #[error("Rate Limited: {0}")]
+    RateLimited(StringType),
+    #[error("Server Error {0}")]
+    ServerError(ApiError),
+    #[error("Api Error {0}")]
+    ApiError(ApiError),
+    #[error(
+        "Retries Exhausted after {attempts} attempts{}: {source}",
+        status.map(|s| crate::format_string!(", last status {s}")).unwrap_or_default()
+    )]
+    RetriesExhausted {
+        attempts: usize,
+        /// Http status of the last response, `None` if the request failed to connect
+        status: Option<u16>,
+        source: Box<Error>,
+    },
 
     #[cfg(feature = "cli")]
     #[error("Clap CLI Parser Error {0}")]
@@ -63,3 +93,28 @@ pub enum Error {
     #[error("Reqwest Error {0}")]
     ReqwestError(#[from] ReqwestError),
 }
+
+impl Error {
+    /// Suggestion on how to fix errors returned by the api
+    #[must_use]
+    pub fn help_message(&self) -> Option<&'static str> {
+        match self {
+            Self::InvalidApiKey(_) => Some(
+                "Please check the api key passed with --api-key or set as API_KEY in \
+                 ~/.config/weather_util/config.env, new keys can take a few hours to activate",
+            ),
+            Self::LocationNotFound(_) => Some(
+                "Please check the zipcode, country code or city name, city names may include \
+                 state and country e.g. \"Astoria,NY,US\"",
+            ),
+            Self::RateLimited(_) => {
+                Some("The api call limit for this key was exceeded, please try again later")
+            }
+            Self::ServerError(_) => {
+                Some("openweathermap.org is unavailable, please try again later")
+            }
+            Self::RetriesExhausted { source, .. } => source.help_message(),
+            _ => None,
+        }
+    }
+}
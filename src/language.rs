@@ -0,0 +1,433 @@
+use serde::{Deserialize, Serialize};
+use time::{Date, OffsetDateTime, Weekday, macros::format_description};
+
+use crate::{StringType, air_quality::AirQualityIndex, beaufort::Beaufort, format_string};
+
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+
+/// Language of api condition descriptions and of the labels, dates and
+/// decimal separators in text output
+/// ```
+/// use weather_util_rust::language::{Language, Message};
+/// assert_eq!(Language::De.get(Message::Sunrise), "Sonnenaufgang");
+/// assert_eq!(&Language::Fr.localize_decimal("3.61 °C"), "3,61 °C");
+/// assert_eq!(&Language::En.localize_decimal("3.61 °C"), "3.61 °C");
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    /// English
+    #[default]
+    En,
+    /// German
+    De,
+    /// French
+    Fr,
+    /// Spanish
+    Es,
+}
+
+/// Labels used in text output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Message {
+    CurrentConditions,
+    LastUpdated,
+    Temperature,
+    RelativeHumidity,
+    Pressure,
+    DewPoint,
+    ApparentTemperature,
+    HeatIndex,
+    WindChill,
+    Humidex,
+    AbsoluteHumidity,
+    Wind,
+    Gusts,
+    Conditions,
+    Sunrise,
+    Sunset,
+    Rain,
+    Snow,
+    Forecast,
+    HourlyForecast,
+    High,
+    Low,
+    FeelsLike,
+    Humidity,
+    Precipitation,
+    Location,
+    HistoricalConditions,
+    Time,
+    NoData,
+    SummaryFor,
+    TotalPrecipitation,
+    MaxWind,
+    DailyForecast,
+    ActiveAlerts,
+    NoActiveAlerts,
+    From,
+    Until,
+    Tags,
+    AirQualityIndex,
+    ForecastChart,
+}
+
+impl Message {
+    /// English, German, French and Spanish text
+    fn translations(self) -> [&'static str; 4] {
+        match self {
+            Self::CurrentConditions => [
+                "Current conditions",
+                "Aktuelles Wetter",
+                "Conditions actuelles",
+                "Condiciones actuales",
+            ],
+            Self::LastUpdated => [
+                "Last Updated",
+                "Zuletzt aktualisiert",
+                "Dernière mise à jour",
+                "Última actualización",
+            ],
+            Self::Temperature => ["Temperature", "Temperatur", "Température", "Temperatura"],
+            Self::RelativeHumidity => [
+                "Relative Humidity",
+                "Relative Luftfeuchtigkeit",
+                "Humidité relative",
+                "Humedad relativa",
+            ],
+            Self::Pressure => ["Pressure", "Luftdruck", "Pression", "Presión"],
+            Self::DewPoint => ["Dew Point", "Taupunkt", "Point de rosée", "Punto de rocío"],
+            Self::ApparentTemperature => [
+                "Apparent Temperature",
+                "Gefühlte Temperatur",
+                "Température apparente",
+                "Temperatura aparente",
+            ],
+            Self::HeatIndex => [
+                "Heat Index",
+                "Hitzeindex",
+                "Indice de chaleur",
+                "Índice de calor",
+            ],
+            Self::WindChill => [
+                "Wind Chill",
+                "Windchill",
+                "Refroidissement éolien",
+                "Sensación por viento",
+            ],
+            Self::Humidex => ["Humidex", "Humidex", "Humidex", "Humidex"],
+            Self::AbsoluteHumidity => [
+                "Absolute Humidity",
+                "Absolute Luftfeuchtigkeit",
+                "Humidité absolue",
+                "Humedad absoluta",
+            ],
+            Self::Wind => ["Wind", "Wind", "Vent", "Viento"],
+            Self::Gusts => ["gusts", "Böen", "rafales", "ráfagas"],
+            Self::Conditions => ["Conditions", "Wetterlage", "Conditions", "Condiciones"],
+            Self::Sunrise => ["Sunrise", "Sonnenaufgang", "Lever du soleil", "Amanecer"],
+            Self::Sunset => [
+                "Sunset",
+                "Sonnenuntergang",
+                "Coucher du soleil",
+                "Atardecer",
+            ],
+            Self::Rain => ["Rain", "Regen", "Pluie", "Lluvia"],
+            Self::Snow => ["Snow", "Schnee", "Neige", "Nieve"],
+            Self::Forecast => ["Forecast", "Vorhersage", "Prévisions", "Pronóstico"],
+            Self::HourlyForecast => [
+                "Hourly Forecast",
+                "Stündliche Vorhersage",
+                "Prévisions horaires",
+                "Pronóstico por horas",
+            ],
+            Self::High => ["High", "Max", "Max", "Máx"],
+            Self::Low => ["Low", "Min", "Min", "Mín"],
+            Self::FeelsLike => ["Feels like", "Gefühlt", "Ressenti", "Sensación"],
+            Self::Humidity => ["Humidity", "Feuchte", "Humidité", "Humedad"],
+            Self::Precipitation => ["Precip", "Niederschlag", "Précip.", "Precip."],
+            Self::Location => ["Location", "Ort", "Lieu", "Ubicación"],
+            Self::HistoricalConditions => [
+                "Historical conditions",
+                "Historisches Wetter",
+                "Conditions historiques",
+                "Condiciones históricas",
+            ],
+            Self::Time => ["Time", "Zeit", "Heure", "Hora"],
+            Self::NoData => ["No data", "Keine Daten", "Aucune donnée", "Sin datos"],
+            Self::SummaryFor => [
+                "Summary for",
+                "Zusammenfassung für",
+                "Résumé du",
+                "Resumen del",
+            ],
+            Self::TotalPrecipitation => [
+                "Precipitation",
+                "Niederschlag",
+                "Précipitations",
+                "Precipitación",
+            ],
+            Self::MaxWind => ["Max Wind", "Max. Wind", "Vent max.", "Viento máx."],
+            Self::DailyForecast => [
+                "Daily Forecast",
+                "Tägliche Vorhersage",
+                "Prévisions quotidiennes",
+                "Pronóstico diario",
+            ],
+            Self::ActiveAlerts => [
+                "Active Alerts",
+                "Aktive Warnungen",
+                "Alertes en cours",
+                "Alertas activas",
+            ],
+            Self::NoActiveAlerts => [
+                "No Active Alerts",
+                "Keine aktiven Warnungen",
+                "Aucune alerte en cours",
+                "Sin alertas activas",
+            ],
+            Self::From => ["From", "Von", "Du", "Desde"],
+            Self::Until => ["Until", "Bis", "Au", "Hasta"],
+            Self::Tags => ["Tags", "Schlagwörter", "Étiquettes", "Etiquetas"],
+            Self::AirQualityIndex => [
+                "Air Quality Index",
+                "Luftqualitätsindex",
+                "Indice de qualité de l'air",
+                "Índice de calidad del aire",
+            ],
+            Self::ForecastChart => [
+                "Forecast Chart",
+                "Vorhersagediagramm",
+                "Graphique des prévisions",
+                "Gráfico del pronóstico",
+            ],
+        }
+    }
+}
+
+impl Language {
+    /// Value of the `lang` query parameter of the openweathermap.org api
+    #[must_use]
+    pub fn api_code(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::De => "de",
+            Self::Fr => "fr",
+            Self::Es => "es",
+        }
+    }
+
+    #[must_use]
+    pub fn get(self, message: Message) -> &'static str {
+        message.translations()[self as usize]
+    }
+
+    /// Description of the Beaufort force, e.g. `moderate breeze`
+    #[must_use]
+    pub fn beaufort(self, beaufort: Beaufort) -> &'static str {
+        const DE: [&str; 13] = [
+            "Windstille",
+            "leiser Zug",
+            "leichte Brise",
+            "schwache Brise",
+            "mäßige Brise",
+            "frische Brise",
+            "starker Wind",
+            "steifer Wind",
+            "stürmischer Wind",
+            "Sturm",
+            "schwerer Sturm",
+            "orkanartiger Sturm",
+            "Orkan",
+        ];
+        const FR: [&str; 13] = [
+            "calme",
+            "très légère brise",
+            "légère brise",
+            "petite brise",
+            "jolie brise",
+            "bonne brise",
+            "vent frais",
+            "grand frais",
+            "coup de vent",
+            "fort coup de vent",
+            "tempête",
+            "violente tempête",
+            "ouragan",
+        ];
+        const ES: [&str; 13] = [
+            "calma",
+            "ventolina",
+            "flojito",
+            "flojo",
+            "bonancible",
+            "fresquito",
+            "fresco",
+            "frescachón",
+            "temporal",
+            "temporal fuerte",
+            "temporal duro",
+            "temporal muy duro",
+            "huracán",
+        ];
+        let force = beaufort.force() as usize;
+        match self {
+            Self::En => beaufort.description(),
+            Self::De => DE[force],
+            Self::Fr => FR[force],
+            Self::Es => ES[force],
+        }
+    }
+
+    /// Description of the air quality index, e.g. `Fair`
+    #[must_use]
+    pub fn air_quality(self, aqi: AirQualityIndex) -> &'static str {
+        const DE: [&str; 5] = ["gut", "mäßig", "mittel", "schlecht", "sehr schlecht"];
+        const FR: [&str; 5] = ["bon", "correct", "moyen", "mauvais", "très mauvais"];
+        const ES: [&str; 5] = ["buena", "aceptable", "moderada", "mala", "muy mala"];
+        let index = u8::from(aqi) as usize - 1;
+        match self {
+            Self::En => aqi.description(),
+            Self::De => DE[index],
+            Self::Fr => FR[index],
+            Self::Es => ES[index],
+        }
+    }
+
+    /// Abbreviated name of the weekday, e.g. `Mon` or `Mo`
+    #[must_use]
+    pub fn weekday(self, weekday: Weekday) -> &'static str {
+        const NAMES: [[&str; 7]; 4] = [
+            ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+            ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+            ["lun", "mar", "mer", "jeu", "ven", "sam", "dim"],
+            ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+        ];
+        NAMES[self as usize][weekday.number_days_from_monday() as usize]
+    }
+
+    #[must_use]
+    pub fn decimal_separator(self) -> char {
+        match self {
+            Self::En => '.',
+            Self::De | Self::Fr | Self::Es => ',',
+        }
+    }
+
+    /// Replace the decimal point of every number in `s` with the decimal
+    /// separator of the language
+    #[must_use]
+    pub fn localize_decimal(self, s: &str) -> StringType {
+        let separator = self.decimal_separator();
+        let chars: Vec<char> = s.chars().collect();
+        chars
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let between_digits = i > 0
+                    && chars[i - 1].is_ascii_digit()
+                    && chars.get(i + 1).is_some_and(char::is_ascii_digit);
+                if *c == '.' && between_digits {
+                    separator
+                } else {
+                    *c
+                }
+            })
+            .collect()
+    }
+
+    /// Format date, e.g. `2022-02-27` or `27.02.2022`
+    #[must_use]
+    pub fn format_date(self, date: Date) -> StringType {
+        let formatted = match self {
+            Self::En => return format_string!("{date}"),
+            Self::De => date.format(format_description!("[day].[month].[year]")),
+            Self::Fr | Self::Es => date.format(format_description!("[day]/[month]/[year]")),
+        };
+        formatted.unwrap_or_default().into()
+    }
+
+    /// Format date and time to the minute, e.g. `2022-02-27 22:00`
+    #[must_use]
+    pub fn format_datetime(self, dt: OffsetDateTime) -> StringType {
+        let time = dt
+            .format(format_description!("[hour]:[minute]"))
+            .unwrap_or_default();
+        format_string!("{} {time}", self.format_date(dt.date()))
+    }
+
+    /// Format date and time with utc offset, English keeps the `Display`
+    /// format of `OffsetDateTime`
+    #[must_use]
+    pub fn format_timestamp(self, dt: OffsetDateTime) -> StringType {
+        if self == Self::En {
+            return format_string!("{dt}");
+        }
+        let offset = dt
+            .format(format_description!(
+                "[offset_hour sign:mandatory]:[offset_minute]"
+            ))
+            .unwrap_or_default();
+        format_string!("{} {offset}", self.format_datetime(dt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{Weekday, macros::datetime};
+
+    use crate::{
+        Error,
+        air_quality::AirQualityIndex,
+        beaufort::Beaufort,
+        language::{Language, Message},
+    };
+
+    #[test]
+    fn test_language() -> Result<(), Error> {
+        for lang in [Language::En, Language::De, Language::Fr, Language::Es] {
+            assert!(!lang.get(Message::Precipitation).is_empty());
+            assert!(!lang.beaufort(Beaufort::Hurricane).is_empty());
+        }
+        assert_eq!(Language::En.get(Message::FeelsLike), "Feels like");
+        assert_eq!(Language::Es.get(Message::Snow), "Nieve");
+        assert_eq!(
+            Language::De.beaufort(Beaufort::ModerateBreeze),
+            "mäßige Brise"
+        );
+        assert_eq!(
+            Language::En.beaufort(Beaufort::ModerateBreeze),
+            "moderate breeze"
+        );
+        assert_eq!(Language::Fr.api_code(), "fr");
+        assert_eq!(Language::En.weekday(Weekday::Monday), "Mon");
+        assert_eq!(Language::De.weekday(Weekday::Sunday), "So");
+        assert_eq!(
+            Language::En.air_quality(AirQualityIndex::VeryPoor),
+            "Very Poor"
+        );
+        assert_eq!(Language::Fr.air_quality(AirQualityIndex::Good), "bon");
+
+        assert_eq!(
+            &Language::De.localize_decimal("40.76000N -73.93000E, Rain 0.41 mm."),
+            "40,76000N -73,93000E, Rain 0,41 mm."
+        );
+
+        let dt = datetime!(2022-02-27 22:05:00 -05:00);
+        assert_eq!(&Language::En.format_date(dt.date()), "2022-02-27");
+        assert_eq!(&Language::De.format_date(dt.date()), "27.02.2022");
+        assert_eq!(&Language::Es.format_datetime(dt), "27/02/2022 22:05");
+        assert_eq!(&Language::En.format_datetime(dt), "2022-02-27 22:05");
+        assert_eq!(&Language::En.format_timestamp(dt), &dt.to_string());
+        assert_eq!(
+            &Language::Fr.format_timestamp(dt),
+            "27/02/2022 22:05 -05:00"
+        );
+
+        let lang: Language = serde_json::from_str(r#""de""#)?;
+        assert_eq!(lang, Language::De);
+        Ok(())
+    }
+}
@@ -25,39 +25,75 @@
 //!     --lon <lon>                      Longitude (must also specify Latitude)
 //! -z, --zipcode <zipcode>              Zipcode (optional)
 
+/// Absolute humidity in g/m³
+pub mod absolute_humidity;
+/// Air Quality Index and pollutant concentrations
+pub mod air_quality;
 pub mod angle;
+/// Error body returned by openweathermap.org
+pub mod api_error;
+/// Beaufort wind force scale
+pub mod beaufort;
+/// Terminal chart of forecast temperature and precipitation
+pub mod chart;
+/// Pollutant concentration in μg/m³
+pub mod concentration;
 /// Configuration data
 pub mod config;
+/// Dew point, heat index, wind chill and other derived quantities
+pub mod derived;
 /// Direction in degrees
 pub mod direction;
 /// Distance in meters
 pub mod distance;
 /// Relative Humidity in percent
 pub mod humidity;
+/// Localized labels, dates and decimal separators
+pub mod language;
 /// Latitude
 pub mod latitude;
+/// Named locations with per-location units and language
+pub mod location_profile;
 /// Longitude
 pub mod longitude;
+/// Representation of One Call 3.0 data from openweathermap.org
+pub mod one_call;
 /// Precipitation (rain/snow) in mm
 pub mod precipitation;
 /// Pressure module: conversions between hPa, kPa, Pa
 pub mod pressure;
+/// Retry policy with exponential backoff for api requests
+pub mod retry_policy;
 /// Speed as meters per second
 pub mod speed;
 /// Temperature module: conversions between Kelvin, Ceclius and Fahrenheit
 pub mod temperature;
+/// One line output templates
+pub mod template;
+/// Options of text output
+pub mod text_options;
 /// Serialize/Deserialize Unix Timetstamp to/from `DateTime`
 pub mod timestamp;
 /// Timezone offset as seconds before / after UTC
 pub mod timezone;
+/// Units used when formatting output
+pub mod unit_system;
 /// Reqwest Client
 pub mod weather_api;
+/// On-disk cache of api responses
+pub mod weather_cache;
 /// Representation of Weather Data from openweathermap.org
 pub mod weather_data;
 /// Representation of Weather Forecast from openweathermap.org
 pub mod weather_forecast;
+/// Emoji, Nerd Font and ascii weather icons
+pub mod weather_icon;
 /// CLI App Options and implementation
 pub mod weather_opts;
+/// Unit-explicit report used for json output
+pub mod weather_report;
+/// Pluggable transport used by `WeatherApi`
+pub mod weather_transport;
 
 /// `WeatherUtil` Error
 pub mod error;
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::{
+    Error, StringType, format_string, language::Language, latitude::Latitude, longitude::Longitude,
+    unit_system::Units, weather_api::WeatherLocation,
+};
+
+/// Named location with optional units and language overrides, written as
+/// `;` separated `key=value` pairs
+/// ```
+/// use weather_util_rust::{
+///     language::Language, location_profile::LocationProfile, unit_system::Units,
+///     weather_api::WeatherLocation,
+/// };
+/// # use anyhow::Error;
+/// # fn main() -> Result<(), Error> {
+/// let profile: LocationProfile = "city_name=Berlin,DE;units=metric;lang=de".parse()?;
+/// assert_eq!(profile.units, Some(Units::Metric));
+/// assert_eq!(profile.lang, Some(Language::De));
+/// assert_eq!(
+///     profile.get_location()?,
+///     WeatherLocation::from_city_name("Berlin,DE")
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LocationProfile {
+    pub zipcode: Option<u64>,
+    pub country_code: Option<StringType>,
+    pub city_name: Option<StringType>,
+    pub lat: Option<Latitude>,
+    pub lon: Option<Longitude>,
+    /// Units used in text output for this location
+    pub units: Option<Units>,
+    /// Language of condition descriptions and text output for this location
+    pub lang: Option<Language>,
+}
+
+impl LocationProfile {
+    /// # Errors
+    ///
+    /// Returns error if the profile has neither a zipcode, a city name nor
+    /// both latitude and longitude
+    pub fn get_location(&self) -> Result<WeatherLocation, Error> {
+        if let Some(zipcode) = self.zipcode {
+            if let Some(country_code) = &self.country_code {
+                Ok(WeatherLocation::from_zipcode_country_code_str(
+                    zipcode,
+                    country_code,
+                ))
+            } else {
+                Ok(WeatherLocation::from_zipcode(zipcode))
+            }
+        } else if let Some(city_name) = &self.city_name {
+            Ok(WeatherLocation::from_city_name(city_name))
+        } else if let Some(lat) = self.lat
+            && let Some(lon) = self.lon
+        {
+            Ok(WeatherLocation::from_lat_lon(lat, lon))
+        } else {
+            Err(Error::InvalidInputError(format_string!(
+                "location needs zipcode, city_name or lat and lon"
+            )))
+        }
+    }
+}
+
+impl FromStr for LocationProfile {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let pairs = s
+            .split(';')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                pair.split_once('=')
+                    .map(|(key, value)| (key.trim().to_lowercase(), value.trim().to_string()))
+                    .ok_or_else(|| {
+                        Error::InvalidInputError(format_string!("expected key=value, got {pair}"))
+                    })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let profile: Self = envy::from_iter(pairs)?;
+        profile.get_location()?;
+        Ok(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Error, language::Language, latitude::Latitude, location_profile::LocationProfile,
+        longitude::Longitude, unit_system::Units, weather_api::WeatherLocation,
+    };
+
+    #[test]
+    fn test_location_profile() -> Result<(), Error> {
+        let profile: LocationProfile = "zipcode=11106; country_code=US; units=imperial".parse()?;
+        assert_eq!(profile.units, Some(Units::Imperial));
+        assert_eq!(profile.lang, None);
+        assert_eq!(
+            profile.get_location()?,
+            WeatherLocation::from_zipcode_country_code_str(11106, "US")
+        );
+
+        let profile: LocationProfile = "lat=40.76;lon=-73.93;lang=fr".parse()?;
+        let lat: Latitude = 40.76.try_into()?;
+        let lon: Longitude = (-73.93).try_into()?;
+        assert_eq!(
+            profile.get_location()?,
+            WeatherLocation::from_lat_lon(lat, lon)
+        );
+        assert_eq!(profile.lang, Some(Language::Fr));
+
+        assert!("lat=40.76".parse::<LocationProfile>().is_err());
+        assert!("city_name".parse::<LocationProfile>().is_err());
+        assert!(
+            "city_name=Paris;color=blue"
+                .parse::<LocationProfile>()
+                .is_err()
+        );
+        assert!("zipcode=abc".parse::<LocationProfile>().is_err());
+        Ok(())
+    }
+}
@@ -1,22 +1,31 @@
+#[cfg(feature = "cli")]
+use std::process::ExitCode;
+
 #[cfg(feature = "cli")]
 use weather_util_rust::{Error, config::Config, weather_opts::WeatherOpts};
 
 #[cfg(feature = "cli")]
 #[tokio::main]
-async fn main() -> Result<(), Error> {
+async fn main() -> Result<ExitCode, Error> {
     let config = Config::init_config(None)?;
 
     match tokio::spawn(async move { WeatherOpts::parse_opts(&config).await })
         .await
         .unwrap()
     {
-        Ok(()) => Ok(()),
+        Ok(exit_code) => Ok(exit_code),
         Err(Error::InvalidInputError(e)) => {
             let help_message = WeatherOpts::api_help_msg();
             println!("{e}\n{help_message}");
-            Ok(())
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(e) => {
+            if let Some(help_message) = e.help_message() {
+                eprintln!("{e}\n{help_message}");
+                return Ok(ExitCode::FAILURE);
+            }
+            Err(e)
         }
-        Err(e) => Err(e),
     }
 }
 
@@ -0,0 +1,802 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Write};
+use time::{Date, OffsetDateTime, UtcOffset, macros::format_description};
+
+use crate::{
+    StringType,
+    direction::Direction,
+    distance::Distance,
+    format_string,
+    humidity::Humidity,
+    language::{Language, Message},
+    latitude::Latitude,
+    longitude::Longitude,
+    precipitation::Precipitation,
+    pressure::Pressure,
+    speed::Speed,
+    temperature::Temperature,
+    timestamp,
+    timezone::TimeZone,
+    unit_system::UnitSystem,
+    weather_data::{Rain, Snow, WeatherCond},
+};
+
+/// Parts of the One Call response which can be excluded from the request
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OneCallExclude {
+    Current,
+    Minutely,
+    Hourly,
+    Daily,
+    Alerts,
+}
+
+impl OneCallExclude {
+    #[must_use]
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Self::Current => "current",
+            Self::Minutely => "minutely",
+            Self::Hourly => "hourly",
+            Self::Daily => "daily",
+            Self::Alerts => "alerts",
+        }
+    }
+}
+
+impl fmt::Display for OneCallExclude {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+/// Current conditions
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OneCallCurrent {
+    #[serde(with = "timestamp")]
+    pub dt: OffsetDateTime,
+    /// Missing during polar day and polar night
+    #[serde(default, with = "time::serde::timestamp::option")]
+    pub sunrise: Option<OffsetDateTime>,
+    /// Missing during polar day and polar night
+    #[serde(default, with = "time::serde::timestamp::option")]
+    pub sunset: Option<OffsetDateTime>,
+    pub temp: Temperature,
+    pub feels_like: Temperature,
+    pub pressure: Pressure,
+    pub humidity: Humidity,
+    pub dew_point: Temperature,
+    pub uvi: f64,
+    /// Cloudiness in percent
+    pub clouds: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Distance>,
+    pub wind_speed: Speed,
+    pub wind_deg: Direction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind_gust: Option<Speed>,
+    pub weather: Vec<WeatherCond>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rain: Option<Rain>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snow: Option<Snow>,
+}
+
+/// Precipitation for one minute
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct OneCallMinutely {
+    #[serde(with = "timestamp")]
+    pub dt: OffsetDateTime,
+    pub precipitation: Precipitation,
+}
+
+/// Hourly forecast
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OneCallHourly {
+    #[serde(with = "timestamp")]
+    pub dt: OffsetDateTime,
+    pub temp: Temperature,
+    pub feels_like: Temperature,
+    pub pressure: Pressure,
+    pub humidity: Humidity,
+    pub dew_point: Temperature,
+    pub uvi: f64,
+    /// Cloudiness in percent
+    pub clouds: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Distance>,
+    pub wind_speed: Speed,
+    pub wind_deg: Direction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind_gust: Option<Speed>,
+    pub weather: Vec<WeatherCond>,
+    /// Probability of precipitation between 0 and 1
+    pub pop: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rain: Option<Rain>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snow: Option<Snow>,
+}
+
+/// Daily temperatures
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct DailyTemperature {
+    pub day: Temperature,
+    pub min: Temperature,
+    pub max: Temperature,
+    pub night: Temperature,
+    pub eve: Temperature,
+    pub morn: Temperature,
+}
+
+/// Daily feels like temperatures
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct DailyFeelsLike {
+    pub day: Temperature,
+    pub night: Temperature,
+    pub eve: Temperature,
+    pub morn: Temperature,
+}
+
+/// Daily forecast
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OneCallDaily {
+    #[serde(with = "timestamp")]
+    pub dt: OffsetDateTime,
+    /// Missing during polar day and polar night
+    #[serde(default, with = "time::serde::timestamp::option")]
+    pub sunrise: Option<OffsetDateTime>,
+    /// Missing during polar day and polar night
+    #[serde(default, with = "time::serde::timestamp::option")]
+    pub sunset: Option<OffsetDateTime>,
+    #[serde(with = "timestamp")]
+    pub moonrise: OffsetDateTime,
+    #[serde(with = "timestamp")]
+    pub moonset: OffsetDateTime,
+    pub moon_phase: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<StringType>,
+    pub temp: DailyTemperature,
+    pub feels_like: DailyFeelsLike,
+    pub pressure: Pressure,
+    pub humidity: Humidity,
+    pub dew_point: Temperature,
+    pub wind_speed: Speed,
+    pub wind_deg: Direction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind_gust: Option<Speed>,
+    pub weather: Vec<WeatherCond>,
+    /// Cloudiness in percent
+    pub clouds: i64,
+    /// Probability of precipitation between 0 and 1
+    pub pop: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rain: Option<Precipitation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snow: Option<Precipitation>,
+    pub uvi: f64,
+}
+
+/// Weather alert issued by a national weather service
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct OneCallAlert {
+    pub sender_name: StringType,
+    pub event: StringType,
+    #[serde(with = "timestamp")]
+    pub start: OffsetDateTime,
+    #[serde(with = "timestamp")]
+    pub end: OffsetDateTime,
+    pub description: StringType,
+    #[serde(default)]
+    pub tags: Vec<StringType>,
+}
+
+impl OneCallAlert {
+    /// Alert is in effect at `now`
+    #[must_use]
+    pub fn is_active(&self, now: OffsetDateTime) -> bool {
+        self.start <= now && now < self.end
+    }
+
+    /// Format alert with start and end times in the timezone `offset`
+    #[must_use]
+    pub fn get_alert(&self, offset: UtcOffset, lang: Language) -> StringType {
+        let mut output = StringType::new();
+        writeln!(output, "\t{} ({})", self.event, self.sender_name).unwrap_or(());
+        writeln!(
+            output,
+            "\t\t{} {} {} {}",
+            lang.get(Message::From),
+            lang.format_datetime(self.start.to_offset(offset)),
+            lang.get(Message::Until),
+            lang.format_datetime(self.end.to_offset(offset)),
+        )
+        .unwrap_or(());
+        if !self.tags.is_empty() {
+            writeln!(
+                output,
+                "\t\t{}: {}",
+                lang.get(Message::Tags),
+                self.tags.join(", ")
+            )
+            .unwrap_or(());
+        }
+        for line in self.description.lines().filter(|l| !l.trim().is_empty()) {
+            writeln!(output, "\t\t{}", line.trim()).unwrap_or(());
+        }
+        output
+    }
+}
+
+/// Representation of the One Call 3.0 response from openweathermap.org
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OneCall {
+    pub lat: Latitude,
+    pub lon: Longitude,
+    /// IANA timezone name, e.g. `America/New_York`
+    pub timezone: StringType,
+    pub timezone_offset: TimeZone,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<OneCallCurrent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minutely: Option<Vec<OneCallMinutely>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hourly: Option<Vec<OneCallHourly>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub daily: Option<Vec<OneCallDaily>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alerts: Option<Vec<OneCallAlert>>,
+}
+
+impl OneCall {
+    #[must_use]
+    pub fn get_offset(&self) -> UtcOffset {
+        self.timezone_offset.into()
+    }
+
+    /// Alerts in effect at `now`
+    #[must_use]
+    pub fn get_active_alerts(&self, now: OffsetDateTime) -> Vec<&OneCallAlert> {
+        self.alerts
+            .iter()
+            .flatten()
+            .filter(|alert| alert.is_active(now))
+            .collect()
+    }
+
+    /// Get alerts in effect at `now`, times are shown in the local timezone of
+    /// the location
+    /// ```
+    /// # use anyhow::Error;
+    /// # use std::io::Read;
+    /// # use std::fs::File;
+    /// use time::macros::datetime;
+    /// use weather_util_rust::{language::Language, one_call::OneCall};
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut buf = String::new();
+    /// # let mut f = File::open("tests/onecall.json")?;
+    /// # f.read_to_string(&mut buf)?;
+    /// let data: OneCall = serde_json::from_str(&buf)?;
+    ///
+    /// let now = datetime!(2024-03-14 16:00:00 UTC);
+    /// let buf = data.get_alerts(now, Language::En).join("");
+    ///
+    /// assert!(buf.starts_with("\nActive Alerts:"), "{}", buf);
+    /// assert!(buf.contains("Wind Advisory (NWS Upton NY)"), "{}", buf);
+    /// assert!(buf.contains("From 2024-03-14 11:00 Until 2024-03-15 00:00"), "{}", buf);
+    ///
+    /// let now = datetime!(2024-03-15 16:00:00 UTC);
+    /// let buf = data.get_alerts(now, Language::En).join("");
+    /// assert_eq!(buf, "\nNo Active Alerts\n");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn get_alerts(&self, now: OffsetDateTime, lang: Language) -> Vec<StringType> {
+        let alerts = self.get_active_alerts(now);
+        if alerts.is_empty() {
+            return vec![format_string!("\n{}\n", lang.get(Message::NoActiveAlerts))];
+        }
+        let fo = self.get_offset();
+        let mut output = vec![format_string!("\n{}:\n", lang.get(Message::ActiveAlerts))];
+        output.extend(alerts.into_iter().map(|alert| alert.get_alert(fo, lang)));
+        output
+    }
+
+    /// Get daily High and Low Temperatures and Precipitation Probability
+    /// ```
+    /// # use anyhow::Error;
+    /// # use std::io::Read;
+    /// # use std::fs::File;
+    /// use weather_util_rust::{
+    ///     language::Language, one_call::OneCall, unit_system::UnitSystem,
+    /// };
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut buf = String::new();
+    /// # let mut f = File::open("tests/onecall.json")?;
+    /// # f.read_to_string(&mut buf)?;
+    /// let data: OneCall = serde_json::from_str(&buf)?;
+    ///
+    /// let buf = data
+    ///     .get_daily_forecast(UnitSystem::default(), Language::En)
+    ///     .join("");
+    ///
+    /// assert!(buf.starts_with("\nDaily Forecast:"), "{}", buf);
+    /// assert!(buf.contains("2024-03-14 High: 59.2 F / 15.1 C"), "{}", buf);
+    /// assert!(buf.contains("Precip: 65%"), "{}", buf);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn get_daily_forecast(&self, units: UnitSystem, lang: Language) -> Vec<StringType> {
+        let mut output = vec![format_string!("\n{}:\n", lang.get(Message::DailyForecast))];
+        let fo = self.get_offset();
+        let Some(daily) = &self.daily else {
+            return output;
+        };
+        output.extend(daily.iter().map(|day| {
+            let d = lang.format_date(day.dt.to_offset(fo).date());
+            let (h, l) = (day.temp.max, day.temp.min);
+            let high = format_string!(
+                "{}: {}",
+                lang.get(Message::High),
+                units.format_temperature_pair(h, 1)
+            );
+            let low = format_string!(
+                "{}: {}",
+                lang.get(Message::Low),
+                units.format_temperature_pair(l, 1)
+            );
+            let pop = format_string!(
+                "{}: {:0.0}%",
+                lang.get(Message::Precipitation),
+                day.pop * 100.0
+            );
+            let mut rain_snow = String::new();
+            if let Some(r) = day.rain.filter(|r| r.millimeters() > 0.0) {
+                let rain = units.format_precipitation(r, 2);
+                write!(rain_snow, "{} {rain}", lang.get(Message::Rain)).unwrap_or(());
+            }
+            if let Some(s) = day.snow.filter(|s| s.millimeters() > 0.0) {
+                if !rain_snow.is_empty() {
+                    rain_snow.push('\t');
+                }
+                let snow = units.format_precipitation(s, 2);
+                write!(rain_snow, "{} {snow}", lang.get(Message::Snow)).unwrap_or(());
+            }
+            let line = format_string!("{high:25} {low:25} {pop:14} {rain_snow:25}");
+            format_string!("\t{d} {}\n", lang.localize_decimal(&line))
+        }));
+        output
+    }
+}
+
+/// Historical conditions returned by the One Call 3.0 `timemachine` api
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OneCallTimeMachine {
+    pub lat: Latitude,
+    pub lon: Longitude,
+    /// IANA timezone name, e.g. `America/New_York`
+    pub timezone: StringType,
+    pub timezone_offset: TimeZone,
+    pub data: Vec<OneCallCurrent>,
+}
+
+impl OneCallTimeMachine {
+    #[must_use]
+    pub fn get_offset(&self) -> UtcOffset {
+        self.timezone_offset.into()
+    }
+
+    /// Write out formatted information about historical conditions
+    /// ```
+    /// # use anyhow::Error;
+    /// # use std::io::Read;
+    /// # use std::fs::File;
+    /// use weather_util_rust::{
+    ///     language::Language, one_call::OneCallTimeMachine, unit_system::UnitSystem,
+    /// };
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut buf = String::new();
+    /// # let mut f = File::open("tests/onecall_timemachine.json")?;
+    /// # f.read_to_string(&mut buf)?;
+    /// let data: OneCallTimeMachine = serde_json::from_str(&buf)?;
+    ///
+    /// let buf = data.get_historical_conditions(UnitSystem::default(), Language::En);
+    ///
+    /// assert!(buf.starts_with("Historical conditions 40.76080N -73.92950E"), "{}", buf);
+    /// assert!(buf.contains("Temperature: 50.45 F (10.25 C)"), "{}", buf);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn get_historical_conditions(&self, units: UnitSystem, lang: Language) -> StringType {
+        let mut output = format_string!("{} ", lang.get(Message::HistoricalConditions));
+        let fo = self.get_offset();
+        let lat_lon = format_string!("{:0.5}N {:0.5}E", self.lat, self.lon);
+        writeln!(output, "{}", lang.localize_decimal(&lat_lon)).unwrap_or(());
+        let Some(data) = self.data.first() else {
+            writeln!(output, "\t{}", lang.get(Message::NoData)).unwrap_or(());
+            return output;
+        };
+        writeln!(
+            output,
+            "{} {}",
+            lang.get(Message::Time),
+            lang.format_timestamp(data.dt.to_offset(fo))
+        )
+        .unwrap_or(());
+        writeln!(
+            output,
+            "\t{}: {}",
+            lang.get(Message::Temperature),
+            lang.localize_decimal(&units.format_temperature(data.temp, 2))
+        )
+        .unwrap_or(());
+        writeln!(
+            output,
+            "\t{}: {}%",
+            lang.get(Message::RelativeHumidity),
+            data.humidity
+        )
+        .unwrap_or(());
+        writeln!(
+            output,
+            "\t{}: {}",
+            lang.get(Message::Pressure),
+            lang.localize_decimal(&units.format_pressure(data.pressure, 0))
+        )
+        .unwrap_or(());
+        writeln!(
+            output,
+            "\t{}: {}",
+            lang.get(Message::Wind),
+            units.format_wind(data.wind_speed, Some(data.wind_deg), data.wind_gust, lang)
+        )
+        .unwrap_or(());
+        write!(
+            output,
+            "\t{}: {}",
+            lang.get(Message::Conditions),
+            data.weather.first().map_or_else(|| "", |w| &w.description)
+        )
+        .unwrap_or(());
+        for (label, time) in [
+            (Message::Sunrise, data.sunrise),
+            (Message::Sunset, data.sunset),
+        ] {
+            if let Some(time) = time {
+                let time = lang.format_timestamp(time.to_offset(fo));
+                write!(output, "\n\t{}: {time}", lang.get(label)).unwrap_or(());
+            }
+        }
+        for (label, precip) in [
+            (Message::Rain, data.rain.map(|r| r.one_hour)),
+            (Message::Snow, data.snow.map(|s| s.one_hour)),
+        ] {
+            if let Some(precip) = precip {
+                let precip = units.format_precipitation(precip.unwrap_or_default(), 3);
+                write!(
+                    output,
+                    "\n\t{}: {}",
+                    lang.get(label),
+                    lang.localize_decimal(&precip)
+                )
+                .unwrap_or(());
+            }
+        }
+        output.push('\n');
+        output
+    }
+}
+
+/// Cloud cover in percent
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct DaySummaryCloudCover {
+    pub afternoon: f64,
+}
+
+/// Relative humidity in percent
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct DaySummaryHumidity {
+    pub afternoon: f64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct DaySummaryPrecipitation {
+    pub total: Precipitation,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct DaySummaryTemperature {
+    pub min: Temperature,
+    pub max: Temperature,
+    pub afternoon: Temperature,
+    pub night: Temperature,
+    pub evening: Temperature,
+    pub morning: Temperature,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct DaySummaryPressure {
+    pub afternoon: Pressure,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct DaySummaryMaxWind {
+    pub speed: Speed,
+    pub direction: Direction,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct DaySummaryWind {
+    pub max: DaySummaryMaxWind,
+}
+
+/// Aggregated weather for one day returned by the One Call 3.0 `day_summary`
+/// api
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OneCallDaySummary {
+    pub lat: Latitude,
+    pub lon: Longitude,
+    /// Timezone offset, e.g. `-04:00`
+    pub tz: StringType,
+    pub date: Date,
+    pub units: StringType,
+    pub cloud_cover: DaySummaryCloudCover,
+    pub humidity: DaySummaryHumidity,
+    pub precipitation: DaySummaryPrecipitation,
+    pub temperature: DaySummaryTemperature,
+    pub pressure: DaySummaryPressure,
+    pub wind: DaySummaryWind,
+}
+
+impl OneCallDaySummary {
+    /// Parse `tz`, falls back to UTC if it is not a valid offset
+    #[must_use]
+    pub fn get_offset(&self) -> UtcOffset {
+        UtcOffset::parse(
+            &self.tz,
+            format_description!("[offset_hour sign:mandatory]:[offset_minute]"),
+        )
+        .unwrap_or(UtcOffset::UTC)
+    }
+
+    /// Get High and Low Temperatures, total Precipitation and maximum Wind
+    #[must_use]
+    pub fn get_day_summary(&self, units: UnitSystem, lang: Language) -> StringType {
+        let (h, l) = (self.temperature.max, self.temperature.min);
+        let wind = &self.wind.max;
+        let mut output = StringType::new();
+        writeln!(
+            output,
+            "{} {}",
+            lang.get(Message::SummaryFor),
+            lang.format_date(self.date)
+        )
+        .unwrap_or(());
+        for (label, value) in [
+            (Message::High, units.format_temperature_pair(h, 1)),
+            (Message::Low, units.format_temperature_pair(l, 1)),
+            (
+                Message::TotalPrecipitation,
+                units.format_precipitation(self.precipitation.total, 2),
+            ),
+        ] {
+            writeln!(
+                output,
+                "\t{}: {}",
+                lang.get(label),
+                lang.localize_decimal(&value)
+            )
+            .unwrap_or(());
+        }
+        writeln!(
+            output,
+            "\t{}: {}",
+            lang.get(Message::MaxWind),
+            units.format_wind(wind.speed, Some(wind.direction), None, lang)
+        )
+        .unwrap_or(());
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use time::macros::{date, datetime, offset};
+
+    use crate::{
+        Error,
+        language::Language,
+        one_call::{OneCall, OneCallDaySummary, OneCallExclude, OneCallTimeMachine},
+        unit_system::{UnitSystem, Units},
+    };
+
+    #[test]
+    fn test_one_call() -> Result<(), Error> {
+        let buf = include_str!("../tests/onecall.json");
+        let data: OneCall = serde_json::from_str(buf)?;
+        assert_eq!(&data.timezone, "America/New_York");
+        assert_eq!(data.timezone_offset.into_inner(), -14400);
+
+        let current = data.current.as_ref().unwrap();
+        assert_eq!(current.dt, datetime!(2024-03-14 16:00:00 UTC));
+        assert_abs_diff_eq!(current.temp.kelvin(), 285.91);
+        assert_abs_diff_eq!(current.wind_gust.unwrap().mps(), 8.23);
+
+        let minutely = data.minutely.as_ref().unwrap();
+        assert_eq!(minutely.len(), 2);
+        assert_abs_diff_eq!(minutely[1].precipitation.millimeters(), 0.25);
+
+        let hourly = data.hourly.as_ref().unwrap();
+        assert_eq!(hourly.len(), 2);
+        assert_abs_diff_eq!(hourly[1].pop, 0.42);
+        assert_abs_diff_eq!(
+            hourly[1].rain.unwrap().one_hour.unwrap().millimeters(),
+            0.31
+        );
+
+        let daily = data.daily.as_ref().unwrap();
+        assert_eq!(daily.len(), 2);
+        assert_abs_diff_eq!(daily[0].temp.max.kelvin(), 288.25);
+        assert_abs_diff_eq!(daily[0].temp.min.kelvin(), 279.12);
+        assert_abs_diff_eq!(daily[0].rain.unwrap().millimeters(), 2.37);
+        assert_eq!(daily[1].rain, None);
+
+        let mut value: serde_json::Value = serde_json::from_str(buf)?;
+        for key in ["sunrise", "sunset"] {
+            value["current"].as_object_mut().unwrap().remove(key);
+            value["daily"][0].as_object_mut().unwrap().remove(key);
+        }
+        let polar: OneCall = serde_json::from_value(value)?;
+        assert_eq!(polar.current.as_ref().unwrap().sunrise, None);
+        assert_eq!(polar.daily.as_ref().unwrap()[0].sunset, None);
+        assert_eq!(polar.daily.as_ref().unwrap()[1].sunset, daily[1].sunset);
+
+        let alerts = data.alerts.as_ref().unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(&alerts[0].event, "Wind Advisory");
+
+        let forecast = data.get_daily_forecast(UnitSystem::default(), Language::En);
+        assert_eq!(forecast.len(), 3);
+        assert!(forecast[1].contains("Rain 0.09 in"));
+        assert!(forecast[2].contains("Precip: 0%"));
+        let forecast = data.get_daily_forecast(Units::Metric.into(), Language::De);
+        assert_eq!(forecast[0], "\nTägliche Vorhersage:\n");
+        assert!(forecast[1].starts_with("\t14.03.2024 Max: 15,1 °C"));
+        assert!(forecast[1].contains("Regen 2,4 mm"), "{}", forecast[1]);
+
+        let serialized = serde_json::to_string(&data)?;
+        let data2: OneCall = serde_json::from_str(&serialized)?;
+        assert_eq!(data2.daily, data.daily);
+
+        assert_eq!(format!("{}", OneCallExclude::Minutely), "minutely");
+        Ok(())
+    }
+
+    #[test]
+    fn test_alerts() -> Result<(), Error> {
+        let buf = include_str!("../tests/onecall.json");
+        let data: OneCall = serde_json::from_str(buf)?;
+        let alert = &data.alerts.as_ref().unwrap()[0];
+        assert_eq!(alert.start, datetime!(2024-03-14 15:00:00 UTC));
+        assert_eq!(alert.end, datetime!(2024-03-15 04:00:00 UTC));
+        assert_eq!(&alert.tags[0], "Wind");
+
+        assert!(alert.is_active(alert.start));
+        assert!(!alert.is_active(alert.end));
+        assert!(!alert.is_active(datetime!(2024-03-14 14:59:59 UTC)));
+
+        let now = datetime!(2024-03-14 16:00:00 UTC);
+        assert_eq!(data.get_active_alerts(now).len(), 1);
+        let output = data.get_alerts(now, Language::En);
+        assert_eq!(output.len(), 2);
+        assert_eq!(
+            output[1].lines().collect::<Vec<_>>(),
+            vec![
+                "\tWind Advisory (NWS Upton NY)",
+                "\t\tFrom 2024-03-14 11:00 Until 2024-03-15 00:00",
+                "\t\tTags: Wind",
+                "\t\t...WIND ADVISORY IN EFFECT FROM 11 AM TO 11 PM EDT THURSDAY...",
+                "\t\t* WHAT...West winds 20 to 30 mph with gusts up to 50 mph expected.",
+            ]
+        );
+
+        let output = data.get_alerts(now, Language::De);
+        assert_eq!(output[0], "\nAktive Warnungen:\n");
+        assert!(output[1].contains("\t\tVon 14.03.2024 11:00 Bis 15.03.2024 00:00\n"));
+        assert!(output[1].contains("\t\tSchlagwörter: Wind\n"));
+
+        let data = OneCall {
+            alerts: None,
+            ..data
+        };
+        assert!(data.get_active_alerts(now).is_empty());
+        assert_eq!(
+            data.get_alerts(now, Language::En),
+            vec!["\nNo Active Alerts\n"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_time_machine() -> Result<(), Error> {
+        let buf = include_str!("../tests/onecall_timemachine.json");
+        let data: OneCallTimeMachine = serde_json::from_str(buf)?;
+        assert_eq!(data.data.len(), 1);
+        assert_eq!(data.data[0].dt, datetime!(2024-03-14 12:00:00 UTC));
+
+        let output = data.get_historical_conditions(UnitSystem::default(), Language::En);
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines[0], "Historical conditions 40.76080N -73.92950E");
+        assert_eq!(lines[1], "Time 2024-03-14 8:00:00.0 -04:00:00");
+        assert_eq!(lines[3], "\tRelative Humidity: 71%");
+        assert_eq!(lines[4], "\tPressure: 1021 hPa");
+        assert_eq!(lines[5], "\tWind: SW 8 mph, Beaufort 3 gentle breeze");
+        assert_eq!(lines[6], "\tConditions: scattered clouds");
+        assert_eq!(lines[9], "\tRain: 0.012 in");
+        let output = data.get_historical_conditions(Units::Imperial.into(), Language::En);
+        assert!(output.contains("\tPressure: 30.15 inHg\n"), "{output}");
+        let output = data.get_historical_conditions(Units::Metric.into(), Language::Fr);
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines[0], "Conditions historiques 40,76080N -73,92950E");
+        assert_eq!(lines[1], "Heure 14/03/2024 08:00 -04:00");
+        assert_eq!(lines[4], "\tPression: 1021 hPa");
+        assert!(lines[5].starts_with("\tVent: SW 13 km/h"), "{output}");
+
+        let buf = include_str!("../tests/onecall_timemachine_polar.json");
+        let polar: OneCallTimeMachine = serde_json::from_str(buf)?;
+        assert_eq!(polar.data[0].sunrise, None);
+        assert_eq!(polar.data[0].sunset, None);
+        let output = polar.get_historical_conditions(UnitSystem::default(), Language::En);
+        assert!(
+            output.ends_with("\tConditions: overcast clouds\n"),
+            "{output}"
+        );
+        assert!(!output.contains("Sunrise"));
+
+        let data = OneCallTimeMachine {
+            data: vec![],
+            ..data
+        };
+        assert!(
+            data.get_historical_conditions(UnitSystem::default(), Language::En)
+                .ends_with("\tNo data\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_day_summary() -> Result<(), Error> {
+        let buf = include_str!("../tests/onecall_day_summary.json");
+        let data: OneCallDaySummary = serde_json::from_str(buf)?;
+        assert_eq!(data.date, date!(2024 - 03 - 14));
+        assert_eq!(data.get_offset(), offset!(-4));
+        assert_abs_diff_eq!(data.temperature.max.kelvin(), 288.25);
+        assert_abs_diff_eq!(data.precipitation.total.millimeters(), 2.37);
+
+        let metric = data.get_day_summary(Units::Metric.into(), Language::En);
+        assert!(metric.contains("\tHigh: 15.1 °C\n"));
+        assert!(metric.contains("\tPrecipitation: 2.4 mm\n"));
+        assert!(metric.contains("Max Wind: WSW 31 km/h, Beaufort 5 fresh breeze"));
+
+        let output = data.get_day_summary(UnitSystem::default(), Language::En);
+        assert_eq!(
+            output,
+            "Summary for 2024-03-14\n\tHigh: 59.2 F / 15.1 C\n\tLow: 42.7 F / 6.0 C\n\t\
+             Precipitation: 0.09 in\n\tMax Wind: WSW 19 mph, Beaufort 5 fresh breeze\n"
+        );
+
+        let output = data.get_day_summary(Units::Metric.into(), Language::Es);
+        assert!(output.starts_with("Resumen del 14/03/2024\n\tMáx: 15,1 °C\n"));
+        assert!(output.contains("\tPrecipitación: 2,4 mm\n"));
+        assert!(output.contains("\tViento máx.: WSW 31 km/h"));
+
+        let serialized = serde_json::to_string(&data)?;
+        let data2: OneCallDaySummary = serde_json::from_str(&serialized)?;
+        assert_eq!(data2, data);
+        Ok(())
+    }
+}
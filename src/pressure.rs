@@ -6,6 +6,7 @@ const HECTO: f64 = 1.0; // hPa 100 hundred Pa
 const KILO: f64 = 1_000.0 / 100.0;
 const ATM: f64 = 98.0665 * HECTO / KILO;
 const PSI: f64 = 14.223 / (98.0665 * HECTO / KILO);
+const INHG: f64 = 1.0 / 33.863_886;
 
 /// Pressure struct, data is stored as hPa (100 Pa)
 #[nutype(
@@ -67,6 +68,13 @@ impl Pressure {
         Self::try_new(psi / PSI).map_err(Into::into)
     }
 
+    /// # Errors
+    ///
+    /// Will return error if input is less than zero
+    pub fn from_inhg(inhg: f64) -> Result<Self, Error> {
+        Self::try_new(inhg / INHG).map_err(Into::into)
+    }
+
     #[inline]
     #[must_use]
     pub fn kpa(self) -> f64 {
@@ -96,6 +104,13 @@ impl Pressure {
     pub fn psi(self) -> f64 {
         self.into_inner() * PSI
     }
+
+    /// Inches of mercury
+    #[inline]
+    #[must_use]
+    pub fn inhg(self) -> f64 {
+        self.into_inner() * INHG
+    }
 }
 
 #[cfg(test)]
@@ -118,6 +133,10 @@ mod tests {
         assert_eq!(p, p2);
         let p = Pressure::from_hpa(1.0)?;
         assert_eq!(p.hpa(), 1.0);
+        let p = Pressure::from_hpa(1013.25)?;
+        assert_abs_diff_eq!(p.inhg(), 29.921, epsilon = 0.001);
+        let p = Pressure::from_inhg(30.0)?;
+        assert_abs_diff_eq!(p.inhg(), 30.0, epsilon = 0.00001);
 
         let p = Pressure::from_hpa(-1.0);
         assert_eq!(
@@ -0,0 +1,189 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+use time::{OffsetDateTime, format_description::well_known::Rfc2822};
+
+const DEFAULT_MAX_ATTEMPTS: usize = 3;
+const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+const DEFAULT_RETRYABLE_STATUSES: [u16; 5] = [429, 500, 502, 503, 504];
+
+/// Policy used by `WeatherApi` to retry requests failing with a retryable
+/// status or a connection error, delays grow exponentially from `base_delay` up to `max_delay`
+/// unless the server sends a `Retry-After` header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of requests, including the first one
+    pub max_attempts: usize,
+    /// Delay before the first retry
+    pub base_delay: Duration,
+    /// Upper bound on any delay, including `Retry-After`
+    pub max_delay: Duration,
+    /// Randomize each delay between half and all of its value
+    pub jitter: bool,
+    /// Http statuses which will be retried
+    pub retryable_statuses: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            jitter: true,
+            retryable_statuses: DEFAULT_RETRYABLE_STATUSES.to_vec(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy which never retries
+    #[must_use]
+    pub fn no_retry() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn with_max_attempts(self, max_attempts: usize) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_base_delay(self, base_delay: Duration) -> Self {
+        Self { base_delay, ..self }
+    }
+
+    #[must_use]
+    pub fn with_max_delay(self, max_delay: Duration) -> Self {
+        Self { max_delay, ..self }
+    }
+
+    #[must_use]
+    pub fn with_jitter(self, jitter: bool) -> Self {
+        Self { jitter, ..self }
+    }
+
+    #[must_use]
+    pub fn with_retryable_statuses(self, retryable_statuses: &[u16]) -> Self {
+        Self {
+            retryable_statuses: retryable_statuses.to_vec(),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn is_retryable(&self, status: u16) -> bool {
+        self.retryable_statuses.contains(&status)
+    }
+
+    /// Delay before the next request after `attempts` failed requests,
+    /// `retry_after` takes precedence over the exponential backoff.
+    /// ```
+    /// use std::time::Duration;
+    /// use weather_util_rust::retry_policy::RetryPolicy;
+    ///
+    /// let policy = RetryPolicy::default().with_jitter(false);
+    /// assert_eq!(policy.get_delay(1, None), Duration::from_millis(500));
+    /// assert_eq!(policy.get_delay(3, None), Duration::from_millis(2000));
+    /// assert_eq!(
+    ///     policy.get_delay(1, Some(Duration::from_secs(5))),
+    ///     Duration::from_secs(5)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn get_delay(&self, attempts: usize, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(self.max_delay);
+        }
+        let exponent = attempts.saturating_sub(1).min(31) as u32;
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.pow(exponent))
+            .min(self.max_delay);
+        if self.jitter {
+            let fraction = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+            delay.mul_f64(0.5 + fraction / 2.0)
+        } else {
+            delay
+        }
+    }
+}
+
+/// Parse the value of a `Retry-After` header, either a number of seconds or
+/// an http date
+#[must_use]
+pub fn parse_retry_after(value: &str, now: OffsetDateTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = OffsetDateTime::parse(value, &Rfc2822).ok()?;
+    Some((date - now).try_into().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use time::macros::datetime;
+
+    use crate::retry_policy::{RetryPolicy, parse_retry_after};
+
+    #[test]
+    fn test_retry_policy() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.max_attempts, 3);
+        assert!(policy.is_retryable(429));
+        assert!(policy.is_retryable(502));
+        assert!(!policy.is_retryable(401));
+        assert!(!policy.is_retryable(404));
+
+        for attempts in 1..5 {
+            let delay = policy.get_delay(attempts, None);
+            let expected = Duration::from_millis(500 * 2u64.pow(attempts as u32 - 1));
+            assert!(delay >= expected / 2);
+            assert!(delay <= expected);
+        }
+
+        let policy = policy
+            .with_jitter(false)
+            .with_max_delay(Duration::from_secs(1))
+            .with_retryable_statuses(&[503])
+            .with_max_attempts(0);
+        assert_eq!(policy.max_attempts, 1);
+        assert!(!policy.is_retryable(502));
+        assert_eq!(policy.get_delay(2, None), Duration::from_secs(1));
+        assert_eq!(policy.get_delay(100, None), Duration::from_secs(1));
+        assert_eq!(
+            policy.get_delay(1, Some(Duration::from_secs(60))),
+            Duration::from_secs(1)
+        );
+        assert_eq!(RetryPolicy::no_retry().max_attempts, 1);
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = datetime!(2015-10-21 07:27:00 UTC);
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT", now),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:26:00 GMT", now),
+            Some(Duration::from_secs(0))
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+}
@@ -1,9 +1,11 @@
 use nutype::nutype;
 
-use crate::Error;
+use crate::{Error, beaufort::Beaufort};
 
 const SECONDS_PER_HOUR: f64 = 3600.;
 const METERS_PER_MILE: f64 = 1609.344;
+const METERS_PER_KILOMETER: f64 = 1000.;
+const METERS_PER_NAUTICAL_MILE: f64 = 1852.;
 
 /// Speed in meters per second
 #[nutype(
@@ -44,6 +46,20 @@ impl Speed {
         Self::try_new(mph * METERS_PER_MILE / SECONDS_PER_HOUR).map_err(Into::into)
     }
 
+    /// # Errors
+    ///
+    /// Will return error if input is less than zero
+    pub fn from_kph(kph: f64) -> Result<Self, Error> {
+        Self::try_new(kph * METERS_PER_KILOMETER / SECONDS_PER_HOUR).map_err(Into::into)
+    }
+
+    /// # Errors
+    ///
+    /// Will return error if input is less than zero
+    pub fn from_knots(knots: f64) -> Result<Self, Error> {
+        Self::try_new(knots * METERS_PER_NAUTICAL_MILE / SECONDS_PER_HOUR).map_err(Into::into)
+    }
+
     #[inline]
     #[must_use]
     pub fn mps(self) -> f64 {
@@ -55,6 +71,23 @@ impl Speed {
     pub fn mph(self) -> f64 {
         self.into_inner() * SECONDS_PER_HOUR / METERS_PER_MILE
     }
+
+    #[inline]
+    #[must_use]
+    pub fn kph(self) -> f64 {
+        self.into_inner() * SECONDS_PER_HOUR / METERS_PER_KILOMETER
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn knots(self) -> f64 {
+        self.into_inner() * SECONDS_PER_HOUR / METERS_PER_NAUTICAL_MILE
+    }
+
+    #[must_use]
+    pub fn beaufort(self) -> Beaufort {
+        Beaufort::from_speed(self)
+    }
 }
 
 #[cfg(test)]
@@ -71,6 +104,15 @@ mod tests {
 
         let s = Speed::from_mps(1.0)?;
         assert_abs_diff_eq!(s.mps(), 1.0);
+        assert_abs_diff_eq!(s.kph(), 3.6);
+
+        let s = Speed::from_kph(36.0)?;
+        assert_abs_diff_eq!(s.mps(), 10.0);
+
+        let s = Speed::from_knots(1.0)?;
+        assert_abs_diff_eq!(s.mps(), 1852. / 3600.);
+        assert_abs_diff_eq!(s.knots(), 1.0);
+        assert_abs_diff_eq!(Speed::from_kph(1.852)?.knots(), 1.0);
 
         let s = Speed::from_mps(-1.0);
         assert_eq!(&format!("{s:?}"), "Err(SpeedError(GreaterOrEqualViolated))");
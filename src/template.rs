@@ -0,0 +1,623 @@
+use std::{collections::BTreeMap, fmt::Write, iter::Peekable, str::Chars, str::FromStr};
+use time::{OffsetDateTime, macros::format_description};
+
+use crate::{
+    Error, StringType,
+    direction::CompassPoints,
+    format_string,
+    precipitation::Precipitation,
+    speed::Speed,
+    temperature::Temperature,
+    weather_data::WeatherData,
+    weather_forecast::{DayBoundary, WeatherForecast},
+    weather_icon::{IconStyle, WeatherIcon},
+};
+
+/// Fields available from current conditions
+pub const CURRENT_FIELDS: &[&str] = &[
+    "name",
+    "country",
+    "lat",
+    "lon",
+    "date",
+    "time",
+    "temp_c",
+    "temp_f",
+    "temp_k",
+    "feels_like_c",
+    "feels_like_f",
+    "feels_like_k",
+    "temp_min_c",
+    "temp_min_f",
+    "temp_max_c",
+    "temp_max_f",
+    "dew_point_c",
+    "dew_point_f",
+    "humidity",
+    "pressure_hpa",
+    "pressure_inhg",
+    "wind_mps",
+    "wind_kmh",
+    "wind_mph",
+    "wind_kn",
+    "wind_deg",
+    "wind_compass",
+    "gust_mps",
+    "gust_kmh",
+    "gust_mph",
+    "gust_kn",
+    "beaufort",
+    "beaufort_desc",
+    "condition",
+    "main",
+    "icon",
+    "icon_code",
+    "clouds",
+    "visibility_km",
+    "visibility_mi",
+    "rain_mm",
+    "rain_in",
+    "snow_mm",
+    "snow_in",
+    "sunrise",
+    "sunset",
+    "timezone",
+    "city_id",
+];
+
+/// Fields available for each forecast day, prefixed by `day0_`, `day1_`, ...
+pub const DAY_FIELDS: &[&str] = &[
+    "date",
+    "weekday",
+    "high_c",
+    "high_f",
+    "low_c",
+    "low_f",
+    "rain_mm",
+    "rain_in",
+    "snow_mm",
+    "snow_in",
+    "condition",
+    "icon",
+    "pop",
+    "humidity",
+];
+
+/// Value of a template field
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateValue {
+    Number(f64),
+    Text(StringType),
+}
+
+impl From<f64> for TemplateValue {
+    fn from(item: f64) -> Self {
+        Self::Number(item)
+    }
+}
+
+impl From<&str> for TemplateValue {
+    fn from(item: &str) -> Self {
+        Self::Text(item.into())
+    }
+}
+
+impl From<StringType> for TemplateValue {
+    fn from(item: StringType) -> Self {
+        Self::Text(item)
+    }
+}
+
+impl TemplateValue {
+    /// Non-zero numbers and non-empty text
+    #[must_use]
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Self::Number(n) => *n != 0.0,
+            Self::Text(s) => !s.is_empty(),
+        }
+    }
+
+    fn format(&self, spec: FormatSpec) -> StringType {
+        let (value, default_align) = match self {
+            Self::Number(n) => {
+                let mut value = match spec.precision {
+                    Some(p) => format_string!("{n:.p$}"),
+                    None => trim_zeros(format_string!("{n:.2}")),
+                };
+                // avoid printing -0 for values which round to zero
+                if value.starts_with('-') && value[1..].chars().all(|c| c == '0' || c == '.') {
+                    value = value[1..].into();
+                }
+                (value, '>')
+            }
+            Self::Text(s) => match spec.precision {
+                Some(p) => (s.chars().take(p).collect(), '<'),
+                None => (s.clone(), '<'),
+            },
+        };
+        let padding = spec.width.saturating_sub(value.chars().count());
+        if padding == 0 {
+            return value;
+        }
+        let (left, right) = match spec.align.unwrap_or(default_align) {
+            '<' => (0, padding),
+            '^' => (padding / 2, padding - padding / 2),
+            _ => (padding, 0),
+        };
+        format_string!("{:left$}{value}{:right$}", "", "")
+    }
+}
+
+fn trim_zeros(value: StringType) -> StringType {
+    if value.contains('.') {
+        value.trim_end_matches('0').trim_end_matches('.').into()
+    } else {
+        value
+    }
+}
+
+/// Flattened view of current conditions and forecast days used to render a
+/// `Template`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TemplateFields(BTreeMap<StringType, TemplateValue>);
+
+fn render_icon(icon: Option<WeatherIcon>, icons: IconStyle) -> StringType {
+    let icons = if icons == IconStyle::None {
+        IconStyle::Emoji
+    } else {
+        icons
+    };
+    icon.map_or_else(StringType::new, |i| i.render(icons).into())
+}
+
+fn format_time(dt: OffsetDateTime) -> StringType {
+    dt.format(format_description!("[hour]:[minute]"))
+        .unwrap_or_default()
+        .into()
+}
+
+impl TemplateFields {
+    /// Fields of the current conditions, `icon` is rendered with `icons`
+    /// (emoji if `IconStyle::None`)
+    #[must_use]
+    pub fn from_weather_data(data: &WeatherData, icons: IconStyle) -> Self {
+        let mut fields = Self::default();
+        fields.insert("name", data.name.as_str());
+        fields.insert_opt("country", data.sys.country.as_ref());
+        fields.insert("lat", f64::from(data.coord.lat));
+        fields.insert("lon", f64::from(data.coord.lon));
+        let dt = data.get_dt();
+        fields.insert("date", format_string!("{}", dt.date()));
+        fields.insert("time", format_time(dt));
+        fields.insert_temperature("temp", data.main.temp);
+        fields.insert_temperature("feels_like", data.main.feels_like);
+        fields.insert_temperature("temp_min", data.main.temp_min);
+        fields.insert_temperature("temp_max", data.main.temp_max);
+        if let Some(dew_point) = data.get_derived().dew_point {
+            fields.insert_temperature("dew_point", dew_point);
+        }
+        fields.insert("humidity", data.main.humidity.into_inner() as f64);
+        fields.insert("pressure_hpa", data.main.pressure.hpa());
+        fields.insert("pressure_inhg", data.main.pressure.inhg());
+        fields.insert_speed("wind", data.wind.speed);
+        if let Some(deg) = data.wind.deg {
+            fields.insert("wind_deg", deg.deg());
+            fields.insert("wind_compass", deg.compass_point(CompassPoints::Sixteen));
+        }
+        if let Some(gust) = data.wind.gust {
+            fields.insert_speed("gust", gust);
+        }
+        let beaufort = data.wind.speed.beaufort();
+        fields.insert("beaufort", beaufort.force() as f64);
+        fields.insert("beaufort_desc", beaufort.description());
+        if let Some(w) = data.weather.first() {
+            fields.insert("condition", w.description.as_str());
+            fields.insert("main", w.main.as_str());
+            fields.insert("icon", render_icon(w.get_icon(), icons));
+            fields.insert("icon_code", w.icon.as_str());
+        }
+        if let Some(clouds) = data.clouds {
+            fields.insert("clouds", clouds.all as f64);
+        }
+        if let Some(visibility) = data.visibility {
+            fields.insert("visibility_km", visibility.meters() / 1000.0);
+            fields.insert("visibility_mi", visibility.miles());
+        }
+        if let Some(rain) = data.rain.and_then(|r| r.one_hour) {
+            fields.insert_precipitation("rain", rain);
+        }
+        if let Some(snow) = data.snow.and_then(|s| s.one_hour) {
+            fields.insert_precipitation("snow", snow);
+        }
+        fields.insert("sunrise", format_time(data.get_sunrise()));
+        fields.insert("sunset", format_time(data.get_sunset()));
+        fields.insert("timezone", format_string!("{}", data.get_offset()));
+        if let Some(id) = data.id {
+            fields.insert("city_id", id as f64);
+        }
+        fields
+    }
+
+    /// Add `day0_*`, `day1_*`, ... fields, one set per forecast day
+    #[must_use]
+    pub fn with_forecast(
+        mut self,
+        forecast: &WeatherForecast,
+        boundary: DayBoundary,
+        icons: IconStyle,
+    ) -> Self {
+        let weekday = format_description!("[weekday repr:short]");
+        for (i, summary) in forecast
+            .get_daily_summaries(boundary)
+            .into_values()
+            .enumerate()
+        {
+            let prefix = format_string!("day{i}_");
+            let mut day = Self::default();
+            day.insert("date", format_string!("{}", summary.date));
+            day.insert(
+                "weekday",
+                summary.date.format(weekday).unwrap_or_default().as_str(),
+            );
+            day.insert_temperature("high", summary.high);
+            day.insert_temperature("low", summary.low);
+            day.insert_precipitation("rain", summary.rain);
+            day.insert_precipitation("snow", summary.snow);
+            day.insert_opt("condition", summary.condition.as_ref());
+            day.insert("icon", render_icon(summary.get_icon(), icons));
+            if let Some(pop) = summary.max_pop {
+                day.insert("pop", pop * 100.0);
+            }
+            day.insert("humidity", summary.mean_humidity);
+            for (name, value) in day.0 {
+                self.0.insert(format_string!("{prefix}{name}"), value);
+            }
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&TemplateValue> {
+        self.0.get(name)
+    }
+
+    fn insert(&mut self, name: &str, value: impl Into<TemplateValue>) {
+        self.0.insert(name.into(), value.into());
+    }
+
+    fn insert_opt(&mut self, name: &str, value: Option<&StringType>) {
+        if let Some(value) = value {
+            self.insert(name, value.clone());
+        }
+    }
+
+    fn insert_temperature(&mut self, name: &str, t: Temperature) {
+        self.insert(&format_string!("{name}_c"), t.celcius());
+        self.insert(&format_string!("{name}_f"), t.fahrenheit());
+        self.insert(&format_string!("{name}_k"), t.kelvin());
+    }
+
+    fn insert_speed(&mut self, name: &str, s: Speed) {
+        self.insert(&format_string!("{name}_mps"), s.mps());
+        self.insert(&format_string!("{name}_kmh"), s.kph());
+        self.insert(&format_string!("{name}_mph"), s.mph());
+        self.insert(&format_string!("{name}_kn"), s.knots());
+    }
+
+    fn insert_precipitation(&mut self, name: &str, p: Precipitation) {
+        self.insert(&format_string!("{name}_mm"), p.millimeters());
+        self.insert(&format_string!("{name}_in"), p.inches());
+    }
+}
+
+/// Alignment, width and precision following a `:` in a field, e.g. `{temp_c:>5.1}`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct FormatSpec {
+    align: Option<char>,
+    width: usize,
+    precision: Option<usize>,
+}
+
+impl FromStr for FormatSpec {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidInputError(format_string!("invalid format spec {s}"));
+        let mut spec = Self::default();
+        let mut rest = s;
+        if let Some(c @ ('<' | '>' | '^')) = rest.chars().next() {
+            spec.align = Some(c);
+            rest = &rest[1..];
+        }
+        let (width, precision) = match rest.split_once('.') {
+            Some((width, precision)) => (width, Some(precision)),
+            None => (rest, None),
+        };
+        if !width.is_empty() {
+            spec.width = width.parse().map_err(|_| invalid())?;
+        }
+        if let Some(precision) = precision {
+            spec.precision = Some(precision.parse().map_err(|_| invalid())?);
+        }
+        Ok(spec)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(StringType),
+    Field {
+        name: StringType,
+        spec: FormatSpec,
+    },
+    /// `{?name}...{/name}` renders its body if the field is truthy,
+    /// `{!name}...{/name}` if it is not
+    Section {
+        name: StringType,
+        negate: bool,
+        body: Vec<Segment>,
+    },
+}
+
+/// One line output template, fields are written as `{name}` or
+/// `{name:spec}` where spec is an optional alignment (`<`, `>` or `^`),
+/// width and precision, `{{` and `}}` are literal braces.
+///
+/// `{?name}...{/name}` is only rendered if the field is present and non-zero,
+/// `{!name}...{/name}` only if it is not.
+/// ```
+/// use weather_util_rust::{
+///     template::{Template, TemplateFields},
+///     weather_data::WeatherData,
+///     weather_icon::IconStyle,
+/// };
+/// # use anyhow::Error;
+/// # use std::io::Read;
+/// # use std::fs::File;
+/// # fn main() -> Result<(), Error> {
+/// # let mut buf = String::new();
+/// # let mut f = File::open("tests/weather.json")?;
+/// # f.read_to_string(&mut buf)?;
+/// let data: WeatherData = serde_json::from_str(&buf)?;
+/// let fields = TemplateFields::from_weather_data(&data, IconStyle::None);
+///
+/// let template: Template = "{name}: {temp_c:.0}°C {condition}{?rain_mm} rain{/rain_mm}".parse()?;
+/// assert_eq!(&template.render(&fields), "Astoria: 4°C broken clouds");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template(Vec<Segment>);
+
+fn split_day_field(name: &str) -> Option<(usize, &str)> {
+    let (day, field) = name.strip_prefix("day")?.split_once('_')?;
+    Some((day.parse().ok()?, field))
+}
+
+fn validate_field(name: &str) -> Result<(), Error> {
+    let known = match split_day_field(name) {
+        Some((_, field)) => DAY_FIELDS.contains(&field),
+        None => CURRENT_FIELDS.contains(&name),
+    };
+    if known {
+        Ok(())
+    } else {
+        Err(Error::InvalidInputError(format_string!(
+            "unknown template field {name}"
+        )))
+    }
+}
+
+fn parse_segments(
+    chars: &mut Peekable<Chars>,
+    closing: Option<&str>,
+) -> Result<Vec<Segment>, Error> {
+    let mut segments = Vec::new();
+    let mut literal = StringType::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.next_if_eq(&'{').is_some() => literal.push('{'),
+            '}' if chars.next_if_eq(&'}').is_some() => literal.push('}'),
+            '}' => {
+                return Err(Error::InvalidInputError("unmatched } in template".into()));
+            }
+            '{' => {
+                let mut tag = StringType::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => tag.push(c),
+                        None => {
+                            return Err(Error::InvalidInputError("unmatched { in template".into()));
+                        }
+                    }
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(literal));
+                    literal = StringType::new();
+                }
+                if let Some(name) = tag.strip_prefix('/') {
+                    if closing == Some(name) {
+                        return Ok(segments);
+                    }
+                    return Err(Error::InvalidInputError(format_string!(
+                        "unexpected {{/{name}}} in template"
+                    )));
+                }
+                let negate = tag.starts_with('!');
+                if let Some(name) = tag.strip_prefix(['?', '!']) {
+                    validate_field(name)?;
+                    let body = parse_segments(chars, Some(name))?;
+                    segments.push(Segment::Section {
+                        name: name.into(),
+                        negate,
+                        body,
+                    });
+                } else {
+                    let (name, spec) = match tag.split_once(':') {
+                        Some((name, spec)) => (name, spec.parse()?),
+                        None => (tag.as_str(), FormatSpec::default()),
+                    };
+                    validate_field(name)?;
+                    segments.push(Segment::Field {
+                        name: name.into(),
+                        spec,
+                    });
+                }
+            }
+            c => literal.push(c),
+        }
+    }
+    if let Some(name) = closing {
+        return Err(Error::InvalidInputError(format_string!(
+            "missing {{/{name}}} in template"
+        )));
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+fn render_segments(segments: &[Segment], fields: &TemplateFields, output: &mut StringType) {
+    for segment in segments {
+        match segment {
+            Segment::Literal(s) => output.push_str(s),
+            Segment::Field { name, spec } => {
+                let value = fields
+                    .get(name)
+                    .map_or_else(|| TemplateValue::Text(StringType::new()), Clone::clone);
+                write!(output, "{}", value.format(*spec)).unwrap_or(());
+            }
+            Segment::Section { name, negate, body } => {
+                let truthy = fields.get(name).is_some_and(TemplateValue::is_truthy);
+                if truthy != *negate {
+                    render_segments(body, fields, output);
+                }
+            }
+        }
+    }
+}
+
+fn any_day_field(segments: &[Segment]) -> bool {
+    segments.iter().any(|segment| match segment {
+        Segment::Literal(_) => false,
+        Segment::Field { name, .. } => split_day_field(name).is_some(),
+        Segment::Section { name, body, .. } => {
+            split_day_field(name).is_some() || any_day_field(body)
+        }
+    })
+}
+
+impl FromStr for Template {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_segments(&mut s.chars().peekable(), None).map(Self)
+    }
+}
+
+impl Template {
+    /// True if the template refers to any `dayN_` field and needs the forecast
+    #[must_use]
+    pub fn uses_forecast(&self) -> bool {
+        any_day_field(&self.0)
+    }
+
+    /// Render the template, missing fields are rendered as empty text
+    #[must_use]
+    pub fn render(&self, fields: &TemplateFields) -> StringType {
+        let mut output = StringType::new();
+        render_segments(&self.0, fields, &mut output);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Error,
+        template::{Template, TemplateFields, TemplateValue},
+        weather_data::WeatherData,
+        weather_forecast::{DayBoundary, WeatherForecast},
+        weather_icon::IconStyle,
+    };
+
+    fn render(template: &str, fields: &TemplateFields) -> Result<String, Error> {
+        let template: Template = template.parse()?;
+        Ok(template.render(fields).to_string())
+    }
+
+    #[test]
+    fn test_template() -> Result<(), Error> {
+        let buf = include_str!("../tests/weather.json");
+        let data: WeatherData = serde_json::from_str(buf)?;
+        let fields = TemplateFields::from_weather_data(&data, IconStyle::Ascii);
+
+        assert_eq!(
+            render(
+                "{name}: {temp_c:.0}°C {condition} {wind_compass} {wind_kmh:.0}km/h",
+                &fields
+            )?,
+            "Astoria: 4°C broken clouds N 19km/h"
+        );
+        assert_eq!(
+            render("{temp_f}|{humidity}|{icon}", &fields)?,
+            "38.5|41|~~~"
+        );
+        assert_eq!(render("[{temp_c:>6.1}]", &fields)?, "[   3.6]");
+        assert_eq!(render("[{main:^8}]", &fields)?, "[ Clouds ]");
+        assert_eq!(render("[{condition:.6}]", &fields)?, "[broken]");
+        assert_eq!(render("{{{country}}}", &fields)?, "{US}");
+        assert_eq!(render("{sunrise}-{sunset}", &fields)?, "06:32-17:44");
+        assert_eq!(render("a{gust_mph}b", &fields)?, "ab");
+        assert_eq!(
+            render(
+                "{?rain_mm}rain {rain_mm}{/rain_mm}{!rain_mm}dry{/rain_mm}",
+                &fields
+            )?,
+            "dry"
+        );
+        assert_eq!(
+            render(
+                "{?wind_kmh}wind {?gust_kmh}gusty{/gust_kmh}{/wind_kmh}",
+                &fields
+            )?,
+            "wind "
+        );
+        assert_eq!(&TemplateValue::Number(-0.2).format(".0".parse()?), "0");
+
+        for invalid in [
+            "{nope}",
+            "{temp_c",
+            "temp_c}",
+            "{temp_c:x}",
+            "{?temp_c}open",
+            "{/temp_c}",
+            "{day0_nope}",
+        ] {
+            assert!(invalid.parse::<Template>().is_err(), "{invalid}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_forecast() -> Result<(), Error> {
+        let buf = include_str!("../tests/weather.json");
+        let data: WeatherData = serde_json::from_str(buf)?;
+        let buf = include_str!("../tests/forecast_full.json");
+        let forecast: WeatherForecast = serde_json::from_str(buf)?;
+
+        let template: Template = "{day1_weekday} {day1_high_c:.0}/{day1_low_c:.0}°C\
+             {?day1_snow_mm} snow {day1_snow_mm:.1}mm{/day1_snow_mm} {day1_icon}"
+            .parse()?;
+        assert!(template.uses_forecast());
+        assert!(!"{temp_c}".parse::<Template>()?.uses_forecast());
+
+        let fields = TemplateFields::from_weather_data(&data, IconStyle::None);
+        assert_eq!(&template.render(&fields), " /°C ");
+
+        let fields = fields.with_forecast(&forecast, DayBoundary::Midnight, IconStyle::Ascii);
+        assert_eq!(&template.render(&fields), "Mon 2/-1°C snow 0.9mm ***");
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{
+    language::Language, unit_system::UnitSystem, weather_forecast::DayBoundary,
+    weather_icon::IconStyle,
+};
+
+/// Units, day boundary, icons and language used in text output
+/// ```
+/// use weather_util_rust::{
+///     language::Language, text_options::TextOptions, unit_system::Units,
+///     weather_forecast::DayBoundary, weather_icon::IconStyle,
+/// };
+/// let options = TextOptions::default()
+///     .with_units(Units::Metric.into())
+///     .with_lang(Language::De);
+/// assert_eq!(options.boundary, DayBoundary::Midnight);
+/// assert_eq!(options.icons, IconStyle::None);
+/// assert_eq!(options.lang, Language::De);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TextOptions {
+    pub units: UnitSystem,
+    /// Where one forecast day ends and the next begins
+    pub boundary: DayBoundary,
+    pub icons: IconStyle,
+    pub lang: Language,
+}
+
+impl TextOptions {
+    #[must_use]
+    pub fn with_units(self, units: UnitSystem) -> Self {
+        Self { units, ..self }
+    }
+
+    #[must_use]
+    pub fn with_boundary(self, boundary: DayBoundary) -> Self {
+        Self { boundary, ..self }
+    }
+
+    #[must_use]
+    pub fn with_icons(self, icons: IconStyle) -> Self {
+        Self { icons, ..self }
+    }
+
+    #[must_use]
+    pub fn with_lang(self, lang: Language) -> Self {
+        Self { lang, ..self }
+    }
+}
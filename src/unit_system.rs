@@ -0,0 +1,395 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    StringType,
+    direction::{CompassPoints, Direction},
+    format_string,
+    language::{Language, Message},
+    precipitation::Precipitation,
+    pressure::Pressure,
+    speed::Speed,
+    temperature::Temperature,
+};
+use std::fmt::Write;
+
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+
+/// Unit used to display temperatures
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureUnit {
+    /// Degrees Celsius
+    #[serde(alias = "c")]
+    #[cfg_attr(feature = "cli", value(alias = "c"))]
+    Celsius,
+    /// Degrees Fahrenheit
+    #[serde(alias = "f")]
+    #[cfg_attr(feature = "cli", value(alias = "f"))]
+    Fahrenheit,
+    /// Kelvin
+    #[serde(alias = "k")]
+    #[cfg_attr(feature = "cli", value(alias = "k"))]
+    Kelvin,
+    /// Degrees Fahrenheit followed by degrees Celsius
+    #[serde(rename = "fahrenheit-celsius")]
+    FahrenheitCelsius,
+}
+
+/// Unit used to display wind speeds
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum SpeedUnit {
+    /// Meters per second
+    #[serde(alias = "m/s")]
+    Mps,
+    /// Kilometers per hour
+    #[serde(alias = "km/h")]
+    Kph,
+    /// Miles per hour
+    Mph,
+    /// Knots
+    #[serde(alias = "kn")]
+    #[cfg_attr(feature = "cli", value(alias = "kn"))]
+    Knots,
+}
+
+/// Unit used to display rain and snow
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum PrecipitationUnit {
+    /// Millimeters
+    #[serde(alias = "millimeters")]
+    Mm,
+    /// Inches
+    #[serde(alias = "inches")]
+    In,
+}
+
+/// Unit used to display atmospheric pressure
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum PressureUnit {
+    /// Hectopascal (millibar)
+    Hpa,
+    /// Inches of mercury
+    Inhg,
+}
+
+/// Predefined sets of units
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    /// °C, km/h, mm and hPa
+    Metric,
+    /// °F, mph, inches and inHg
+    Imperial,
+    /// K, m/s, mm and hPa (as used by the openweathermap.org api)
+    #[serde(alias = "si")]
+    #[cfg_attr(feature = "cli", value(alias = "si"))]
+    Standard,
+    /// °F followed by °C, mph, inches and hPa
+    #[default]
+    Mixed,
+}
+
+/// Units used by the text formatters, either one of the predefined `Units`
+/// or a predefined set with per-quantity overrides
+/// ```
+/// use weather_util_rust::unit_system::{SpeedUnit, UnitSystem, Units};
+/// use weather_util_rust::speed::Speed;
+/// # use anyhow::Error;
+/// # fn main() -> Result<(), Error> {
+/// let units = UnitSystem::from(Units::Standard).with_speed(SpeedUnit::Kph);
+/// assert_eq!(&units.format_speed(Speed::from_mps(10.0)?, 1), "36.0 km/h");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UnitSystem {
+    pub temperature: TemperatureUnit,
+    pub speed: SpeedUnit,
+    pub precipitation: PrecipitationUnit,
+    pub pressure: PressureUnit,
+}
+
+impl Default for UnitSystem {
+    fn default() -> Self {
+        Units::default().into()
+    }
+}
+
+impl From<Units> for UnitSystem {
+    fn from(units: Units) -> Self {
+        let (temperature, speed, precipitation, pressure) = match units {
+            Units::Metric => (
+                TemperatureUnit::Celsius,
+                SpeedUnit::Kph,
+                PrecipitationUnit::Mm,
+                PressureUnit::Hpa,
+            ),
+            Units::Imperial => (
+                TemperatureUnit::Fahrenheit,
+                SpeedUnit::Mph,
+                PrecipitationUnit::In,
+                PressureUnit::Inhg,
+            ),
+            Units::Standard => (
+                TemperatureUnit::Kelvin,
+                SpeedUnit::Mps,
+                PrecipitationUnit::Mm,
+                PressureUnit::Hpa,
+            ),
+            Units::Mixed => (
+                TemperatureUnit::FahrenheitCelsius,
+                SpeedUnit::Mph,
+                PrecipitationUnit::In,
+                PressureUnit::Hpa,
+            ),
+        };
+        Self {
+            temperature,
+            speed,
+            precipitation,
+            pressure,
+        }
+    }
+}
+
+impl UnitSystem {
+    #[must_use]
+    pub fn with_temperature(self, temperature: TemperatureUnit) -> Self {
+        Self {
+            temperature,
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_speed(self, speed: SpeedUnit) -> Self {
+        Self { speed, ..self }
+    }
+
+    #[must_use]
+    pub fn with_precipitation(self, precipitation: PrecipitationUnit) -> Self {
+        Self {
+            precipitation,
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_pressure(self, pressure: PressureUnit) -> Self {
+        Self { pressure, ..self }
+    }
+
+    /// Format temperature with `precision` decimal places, e.g. `12.3 °C`,
+    /// or `54.1 F (12.3 C)` for `TemperatureUnit::FahrenheitCelsius`
+    #[must_use]
+    pub fn format_temperature(&self, t: Temperature, precision: usize) -> StringType {
+        match self.temperature {
+            TemperatureUnit::FahrenheitCelsius => format_string!(
+                "{f:0.precision$} F ({c:0.precision$} C)",
+                f = t.fahrenheit(),
+                c = t.celcius()
+            ),
+            _ => self.format_single_temperature(t, precision),
+        }
+    }
+
+    /// Same as `format_temperature`, but `TemperatureUnit::FahrenheitCelsius`
+    /// separates both values with a slash, e.g. `54.1 F / 12.3 C`, as used in
+    /// forecast tables
+    #[must_use]
+    pub fn format_temperature_pair(&self, t: Temperature, precision: usize) -> StringType {
+        match self.temperature {
+            TemperatureUnit::FahrenheitCelsius => format_string!(
+                "{f:0.precision$} F / {c:0.precision$} C",
+                f = t.fahrenheit(),
+                c = t.celcius()
+            ),
+            _ => self.format_single_temperature(t, precision),
+        }
+    }
+
+    fn format_single_temperature(&self, t: Temperature, precision: usize) -> StringType {
+        match self.temperature {
+            TemperatureUnit::Celsius => format_string!("{:0.precision$} °C", t.celcius()),
+            TemperatureUnit::Fahrenheit | TemperatureUnit::FahrenheitCelsius => {
+                format_string!("{:0.precision$} °F", t.fahrenheit())
+            }
+            TemperatureUnit::Kelvin => format_string!("{:0.precision$} K", t.kelvin()),
+        }
+    }
+
+    /// Format speed with `precision` decimal places, e.g. `12.3 km/h`
+    #[must_use]
+    pub fn format_speed(&self, s: Speed, precision: usize) -> StringType {
+        match self.speed {
+            SpeedUnit::Mps => format_string!("{:0.precision$} m/s", s.mps()),
+            SpeedUnit::Kph => format_string!("{:0.precision$} km/h", s.kph()),
+            SpeedUnit::Mph => format_string!("{:0.precision$} mph", s.mph()),
+            SpeedUnit::Knots => format_string!("{:0.precision$} kn", s.knots()),
+        }
+    }
+
+    /// Format wind as compass point, speed, gusts and Beaufort force, e.g.
+    /// `WNW 15 mph, gusts 25 mph, Beaufort 4 moderate breeze`
+    #[must_use]
+    pub fn format_wind(
+        &self,
+        speed: Speed,
+        direction: Option<Direction>,
+        gust: Option<Speed>,
+        lang: Language,
+    ) -> StringType {
+        let mut output = StringType::new();
+        if let Some(direction) = direction {
+            write!(
+                output,
+                "{} ",
+                direction.compass_point(CompassPoints::Sixteen)
+            )
+            .unwrap_or(());
+        }
+        output.push_str(&self.format_speed(speed, 0));
+        if let Some(gust) = gust {
+            write!(
+                output,
+                ", {} {}",
+                lang.get(Message::Gusts),
+                self.format_speed(gust, 0)
+            )
+            .unwrap_or(());
+        }
+        let beaufort = speed.beaufort();
+        write!(
+            output,
+            ", Beaufort {} {}",
+            beaufort.force(),
+            lang.beaufort(beaufort)
+        )
+        .unwrap_or(());
+        output
+    }
+
+    /// Format precipitation, `precision` is the number of decimal places for
+    /// inches, millimeters use one less (but at least one) decimal place
+    #[must_use]
+    pub fn format_precipitation(&self, p: Precipitation, precision: usize) -> StringType {
+        match self.precipitation {
+            PrecipitationUnit::Mm => {
+                let precision = precision.saturating_sub(1).max(1);
+                format_string!("{:0.precision$} mm", p.millimeters())
+            }
+            PrecipitationUnit::In => format_string!("{:0.precision$} in", p.inches()),
+        }
+    }
+
+    /// Format pressure, `precision` is the number of decimal places for hPa,
+    /// inches of mercury use two more decimal places
+    #[must_use]
+    pub fn format_pressure(&self, p: Pressure, precision: usize) -> StringType {
+        match self.pressure {
+            PressureUnit::Hpa => format_string!("{:0.precision$} hPa", p.hpa()),
+            PressureUnit::Inhg => {
+                let precision = precision + 2;
+                format_string!("{:0.precision$} inHg", p.inhg())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Error,
+        direction::Direction,
+        language::Language,
+        precipitation::Precipitation,
+        pressure::Pressure,
+        speed::Speed,
+        temperature::Temperature,
+        unit_system::{
+            PrecipitationUnit, PressureUnit, SpeedUnit, TemperatureUnit, UnitSystem, Units,
+        },
+    };
+
+    #[test]
+    fn test_unit_system() -> Result<(), Error> {
+        let t = Temperature::from_celcius(12.5)?;
+        let s = Speed::from_mps(5.0)?;
+        let p = Precipitation::from_millimeters(2.54)?;
+        let pr = Pressure::from_hpa(1013.25)?;
+
+        let units = UnitSystem::default();
+        assert_eq!(units, Units::Mixed.into());
+        assert_eq!(&units.format_temperature(t, 2), "54.50 F (12.50 C)");
+        assert_eq!(&units.format_temperature_pair(t, 1), "54.5 F / 12.5 C");
+        assert_eq!(&units.format_speed(s, 2), "11.18 mph");
+        assert_eq!(
+            &units.format_wind(s, Some(Direction::from_deg(290.0)), None, Language::En),
+            "WNW 11 mph, Beaufort 3 gentle breeze"
+        );
+        assert_eq!(&units.format_precipitation(p, 2), "0.10 in");
+        assert_eq!(&units.format_pressure(pr, 0), "1013 hPa");
+
+        let units: UnitSystem = Units::Metric.into();
+        assert_eq!(&units.format_temperature(t, 2), "12.50 °C");
+        assert_eq!(&units.format_temperature_pair(t, 1), "12.5 °C");
+        assert_eq!(&units.format_speed(s, 1), "18.0 km/h");
+        assert_eq!(&units.format_precipitation(p, 3), "2.54 mm");
+        assert_eq!(&units.format_precipitation(p, 1), "2.5 mm");
+
+        let units: UnitSystem = Units::Imperial.into();
+        assert_eq!(&units.format_temperature(t, 1), "54.5 °F");
+        assert_eq!(&units.format_pressure(pr, 0), "29.92 inHg");
+
+        let units: UnitSystem = Units::Standard.into();
+        assert_eq!(&units.format_temperature(t, 2), "285.65 K");
+        assert_eq!(&units.format_speed(s, 2), "5.00 m/s");
+        assert_eq!(
+            &units.format_wind(s, None, Some(Speed::from_mps(9.0)?), Language::En),
+            "5 m/s, gusts 9 m/s, Beaufort 3 gentle breeze"
+        );
+        assert_eq!(
+            &units.format_wind(s, None, Some(Speed::from_mps(9.0)?), Language::De),
+            "5 m/s, Böen 9 m/s, Beaufort 3 schwache Brise"
+        );
+        let units = units.with_speed(SpeedUnit::Knots);
+        assert_eq!(&units.format_speed(s, 1), "9.7 kn");
+
+        let units = UnitSystem::from(Units::Metric)
+            .with_temperature(TemperatureUnit::Fahrenheit)
+            .with_speed(SpeedUnit::Mps)
+            .with_precipitation(PrecipitationUnit::In)
+            .with_pressure(PressureUnit::Inhg);
+        assert_eq!(
+            units,
+            UnitSystem::from(Units::Imperial).with_speed(SpeedUnit::Mps)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_units_serde() -> Result<(), Error> {
+        let units: Units = serde_json::from_str(r#""si""#)?;
+        assert_eq!(units, Units::Standard);
+        let units: TemperatureUnit = serde_json::from_str(r#""c""#)?;
+        assert_eq!(units, TemperatureUnit::Celsius);
+        let units: SpeedUnit = serde_json::from_str(r#""km/h""#)?;
+        assert_eq!(units, SpeedUnit::Kph);
+        let units: UnitSystem = Units::Metric.into();
+        let serialized = serde_json::to_string(&units)?;
+        assert_eq!(
+            serialized,
+            r#"{"temperature":"celsius","speed":"kph","precipitation":"mm","pressure":"hpa"}"#
+        );
+        Ok(())
+    }
+}
@@ -1,31 +1,62 @@
 use isocountry::CountryCode;
+#[cfg(feature = "cli")]
+use log::warn;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     convert::TryInto,
     fmt::{self},
     hash::{Hash, Hasher},
 };
+#[cfg(feature = "cli")]
+use time::{Date, OffsetDateTime};
+
+#[cfg(feature = "cli")]
+use std::sync::Arc;
 
 use crate::Error;
 
 #[cfg(feature = "cli")]
-use reqwest::{Client, Url};
+use reqwest::Url;
+
+#[cfg(feature = "cli")]
+use tokio::time::sleep;
+
+#[cfg(feature = "cli")]
+use crate::{
+    api_error::ApiError,
+    retry_policy::RetryPolicy,
+    weather_cache::{CacheCategory, WeatherCache},
+    weather_transport::{ReqwestTransport, TransportResponse, WeatherTransport},
+};
 
 use crate::{
-    ApiStringType, StringType, apistringtype_from_display, format_string, latitude::Latitude,
-    longitude::Longitude, weather_data::WeatherData, weather_forecast::WeatherForecast,
+    ApiStringType, StringType,
+    air_quality::AirQuality,
+    apistringtype_from_display, format_string,
+    language::Language,
+    latitude::Latitude,
+    longitude::Longitude,
+    one_call::{OneCall, OneCallDaySummary, OneCallExclude, OneCallTimeMachine},
+    weather_data::WeatherData,
+    weather_forecast::WeatherForecast,
 };
 
-/// `WeatherApi` contains a `reqwest` Client and all the metadata required to
-/// query the openweathermap.org api.
+/// `WeatherApi` contains a `WeatherTransport` (by default a `reqwest` Client)
+/// and all the metadata required to query the openweathermap.org api.
 #[cfg(feature = "cli")]
-#[derive(Default, Clone)]
+#[derive(Clone)]
 pub struct WeatherApi {
-    client: Client,
+    transport: Arc<dyn WeatherTransport>,
     api_key: ApiStringType,
     api_endpoint: StringType,
     api_path: StringType,
     geo_path: StringType,
+    one_call_path: StringType,
+    base_url: Option<Url>,
+    cache: Option<WeatherCache>,
+    retry_policy: RetryPolicy,
+    language: Option<Language>,
 }
 
 #[derive(Clone, Debug, PartialEq, Hash, Eq)]
@@ -156,7 +187,15 @@ impl WeatherLocation {
     pub async fn to_lat_lon(&self, api: &WeatherApi) -> Result<Self, Error> {
         match self {
             Self::CityName(city_name) => {
-                if let Some(loc) = api.get_direct_location(city_name).await?.first() {
+                let locations = api.get_direct_location(city_name).await?;
+                if let Some(loc) = locations.first() {
+                    if locations.len() > 1 {
+                        warn!(
+                            "{} locations match {city_name}, using {loc}, run geocode to see all \
+                             of them",
+                            locations.len()
+                        );
+                    }
                     let (latitude, longitude) = loc.get_lat_lon()?;
                     Ok(Self::LatLon {
                         latitude,
@@ -184,12 +223,31 @@ impl WeatherLocation {
     }
 }
 
+#[cfg(feature = "cli")]
+impl Default for WeatherApi {
+    fn default() -> Self {
+        Self {
+            transport: Arc::new(ReqwestTransport::default()),
+            api_key: ApiStringType::default(),
+            api_endpoint: StringType::default(),
+            api_path: StringType::default(),
+            geo_path: StringType::default(),
+            one_call_path: StringType::default(),
+            base_url: None,
+            cache: None,
+            retry_policy: RetryPolicy::default(),
+            language: None,
+        }
+    }
+}
+
 #[cfg(feature = "cli")]
 impl PartialEq for WeatherApi {
     fn eq(&self, other: &Self) -> bool {
         self.api_key == other.api_key
             && self.api_endpoint == other.api_endpoint
             && self.api_path == other.api_path
+            && self.base_url == other.base_url
     }
 }
 
@@ -197,8 +255,12 @@ impl PartialEq for WeatherApi {
 impl fmt::Debug for WeatherApi {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let api_key = &self.api_key;
-        let api_endpoint = &self.api_endpoint;
-        write!(f, "WeatherApi(key={api_key},endpoint={api_endpoint})")
+        if let Some(base_url) = &self.base_url {
+            write!(f, "WeatherApi(key={api_key},endpoint={base_url})")
+        } else {
+            let api_endpoint = &self.api_endpoint;
+            write!(f, "WeatherApi(key={api_key},endpoint={api_endpoint})")
+        }
     }
 }
 
@@ -213,6 +275,9 @@ impl Hash for WeatherApi {
 enum WeatherCommands {
     Weather,
     Forecast,
+    AirPollution,
+    AirPollutionForecast,
+    AirPollutionHistory,
 }
 
 impl WeatherCommands {
@@ -220,6 +285,19 @@ impl WeatherCommands {
         match self {
             Self::Weather => "weather",
             Self::Forecast => "forecast",
+            Self::AirPollution => "air_pollution",
+            Self::AirPollutionForecast => "air_pollution/forecast",
+            Self::AirPollutionHistory => "air_pollution/history",
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    fn cache_category(self) -> CacheCategory {
+        match self {
+            Self::Weather | Self::AirPollution => CacheCategory::Weather,
+            Self::Forecast | Self::AirPollutionForecast | Self::AirPollutionHistory => {
+                CacheCategory::Forecast
+            }
         }
     }
 }
@@ -236,27 +314,54 @@ pub struct GeoLocation {
     pub lat: f64,
     pub lon: f64,
     pub country: StringType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<StringType>,
     pub zip: Option<StringType>,
+    /// Name in other languages keyed by language code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_names: Option<BTreeMap<StringType, StringType>>,
+}
+
+impl fmt::Display for GeoLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(zip) = &self.zip {
+            write!(f, "{zip} ")?;
+        }
+        write!(f, "{}", self.name)?;
+        if let Some(state) = &self.state {
+            write!(f, ", {state}")?;
+        }
+        write!(f, ", {} {:0.5}N {:0.5}E", self.country, self.lat, self.lon)
+    }
 }
 
 impl GeoLocation {
-    fn get_lat_lon(&self) -> Result<(Latitude, Longitude), Error> {
+    /// # Errors
+    ///
+    /// Will return error if latitude or longitude are out of range
+    pub fn get_lat_lon(&self) -> Result<(Latitude, Longitude), Error> {
         Ok((self.lat.try_into()?, self.lon.try_into()?))
     }
 }
 
 #[cfg(feature = "cli")]
 impl WeatherApi {
-    /// Create `WeatherApi` instance specifying `api_key`, `api_endpoint` and
-    /// `api_path`
+    /// Create `WeatherApi` instance specifying `api_key`, `api_endpoint`,
+    /// `api_path` and `geo_path`, the One Call api path defaults to
+    /// `data/3.0/`
     #[must_use]
     pub fn new(api_key: &str, api_endpoint: &str, api_path: &str, geo_path: &str) -> Self {
         Self {
-            client: Client::new(),
+            transport: Arc::new(ReqwestTransport::default()),
             api_key: api_key.into(),
             api_endpoint: api_endpoint.into(),
             api_path: api_path.into(),
             geo_path: geo_path.into(),
+            one_call_path: "data/3.0/".into(),
+            base_url: None,
+            cache: None,
+            retry_policy: RetryPolicy::default(),
+            language: None,
         }
     }
 
@@ -292,6 +397,80 @@ impl WeatherApi {
         }
     }
 
+    #[must_use]
+    pub fn with_one_call_path(self, one_call_path: &str) -> Self {
+        Self {
+            one_call_path: one_call_path.into(),
+            ..self
+        }
+    }
+
+    /// Replace the default `reqwest` transport, e.g. with a
+    /// `FixtureTransport` for offline testing
+    #[must_use]
+    pub fn with_transport(self, transport: impl WeatherTransport + 'static) -> Self {
+        Self {
+            transport: Arc::new(transport),
+            ..self
+        }
+    }
+
+    /// Cache responses on disk using `cache`
+    #[must_use]
+    pub fn with_cache(self, cache: WeatherCache) -> Self {
+        Self {
+            cache: Some(cache),
+            ..self
+        }
+    }
+
+    /// Retry requests failing with a retryable status or a connection error
+    /// according to `retry_policy`
+    #[must_use]
+    pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            ..self
+        }
+    }
+
+    /// Request condition descriptions in `language`, the `lang` parameter is
+    /// sent with every weather, forecast, air pollution and One Call request
+    #[must_use]
+    pub fn with_language(self, language: Language) -> Self {
+        Self {
+            language: Some(language),
+            ..self
+        }
+    }
+
+    /// Use a full base url (scheme, host, port and path prefix) instead of
+    /// `https://{api_endpoint}/`, e.g. `http://localhost:8080/owm/`
+    /// # Errors
+    ///
+    /// Will return error if `base_url` is not a valid http(s) base url
+    pub fn with_base_url(self, base_url: &str) -> Result<Self, Error> {
+        let base_url = parse_base_url(base_url)?;
+        Ok(Self {
+            base_url: Some(base_url),
+            ..self
+        })
+    }
+
+    /// Base url used for all requests, either the url set by
+    /// `WeatherApi::with_base_url` or `https://{api_endpoint}/`
+    /// # Errors
+    ///
+    /// Will return error if `api_endpoint` does not form a valid url
+    pub fn get_base_url(&self) -> Result<Url, Error> {
+        if let Some(base_url) = &self.base_url {
+            Ok(base_url.clone())
+        } else {
+            let api_endpoint = &self.api_endpoint;
+            parse_base_url(&format_string!("https://{api_endpoint}/"))
+        }
+    }
+
     /// Get `WeatherData` from api
     /// # Errors
     ///
@@ -313,9 +492,118 @@ impl WeatherApi {
         self.run_api(WeatherCommands::Forecast, &options).await
     }
 
+    /// Get current `AirQuality` from api, `location` is converted to
+    /// latitude/longitude first
+    /// # Errors
+    ///
+    /// Will return error if `WeatherLocation::to_lat_lon` or
+    /// `WeatherApi::run_api` fails
+    pub async fn get_air_pollution(&self, location: &WeatherLocation) -> Result<AirQuality, Error> {
+        let location = location.to_lat_lon(self).await?;
+        let options = self.get_options(&location);
+        self.run_api(WeatherCommands::AirPollution, &options).await
+    }
+
+    /// Get hourly `AirQuality` forecast from api, `location` is converted to
+    /// latitude/longitude first
+    /// # Errors
+    ///
+    /// Will return error if `WeatherLocation::to_lat_lon` or
+    /// `WeatherApi::run_api` fails
+    pub async fn get_air_pollution_forecast(
+        &self,
+        location: &WeatherLocation,
+    ) -> Result<AirQuality, Error> {
+        let location = location.to_lat_lon(self).await?;
+        let options = self.get_options(&location);
+        self.run_api(WeatherCommands::AirPollutionForecast, &options)
+            .await
+    }
+
+    /// Get historical `AirQuality` between `start` and `end` from api,
+    /// `location` is converted to latitude/longitude first
+    /// # Errors
+    ///
+    /// Will return error if `WeatherLocation::to_lat_lon` or
+    /// `WeatherApi::run_api` fails
+    pub async fn get_air_pollution_history(
+        &self,
+        location: &WeatherLocation,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+    ) -> Result<AirQuality, Error> {
+        let location = location.to_lat_lon(self).await?;
+        let mut options = self.get_options(&location);
+        options.push(("start", format_string!("{}", start.unix_timestamp()).into()));
+        options.push(("end", format_string!("{}", end.unix_timestamp()).into()));
+        self.run_api(WeatherCommands::AirPollutionHistory, &options)
+            .await
+    }
+
+    /// Get `OneCall` from the One Call 3.0 api, `location` is converted to
+    /// latitude/longitude first, parts listed in `exclude` are omitted from
+    /// the response
+    /// # Errors
+    ///
+    /// Will return error if `WeatherLocation::to_lat_lon` or
+    /// `WeatherApi::run_one_call` fails
+    pub async fn get_one_call(
+        &self,
+        location: &WeatherLocation,
+        exclude: &[OneCallExclude],
+    ) -> Result<OneCall, Error> {
+        let location = location.to_lat_lon(self).await?;
+        let mut options = self.get_options(&location);
+        if !exclude.is_empty() {
+            let exclude: Vec<_> = exclude.iter().map(|e| e.to_str()).collect();
+            options.push(("exclude", exclude.join(",").into()));
+        }
+        self.run_one_call("onecall", &options, CacheCategory::Weather)
+            .await
+    }
+
+    /// Get historical conditions at time `dt` from the One Call 3.0
+    /// `timemachine` api, `location` is converted to latitude/longitude first
+    /// # Errors
+    ///
+    /// Will return error if `WeatherLocation::to_lat_lon` or
+    /// `WeatherApi::run_one_call` fails
+    pub async fn get_historical(
+        &self,
+        location: &WeatherLocation,
+        dt: OffsetDateTime,
+    ) -> Result<OneCallTimeMachine, Error> {
+        let location = location.to_lat_lon(self).await?;
+        let mut options = self.get_options(&location);
+        options.push(("dt", format_string!("{}", dt.unix_timestamp()).into()));
+        self.run_one_call("onecall/timemachine", &options, CacheCategory::Forecast)
+            .await
+    }
+
+    /// Get aggregated weather for `date` from the One Call 3.0 `day_summary`
+    /// api, `location` is converted to latitude/longitude first
+    /// # Errors
+    ///
+    /// Will return error if `WeatherLocation::to_lat_lon` or
+    /// `WeatherApi::run_one_call` fails
+    pub async fn get_day_summary(
+        &self,
+        location: &WeatherLocation,
+        date: Date,
+    ) -> Result<OneCallDaySummary, Error> {
+        let location = location.to_lat_lon(self).await?;
+        let mut options = self.get_options(&location);
+        options.push(("date", format_string!("{date}").into()));
+        self.run_one_call("onecall/day_summary", &options, CacheCategory::Forecast)
+            .await
+    }
+
     fn get_options(&self, location: &WeatherLocation) -> Vec<(&'static str, ApiStringType)> {
         let mut options = location.get_options();
         options.push(("appid", self.api_key.clone()));
+        if let Some(language) = self.language {
+            options.push(("lang", language.api_code().into()));
+        }
         options
     }
 
@@ -324,10 +612,10 @@ impl WeatherApi {
         command: WeatherCommands,
         options: &[(&'static str, ApiStringType)],
     ) -> Result<T, Error> {
-        let api_endpoint = &self.api_endpoint;
         let api_path = &self.api_path;
+        let category = command.cache_category();
         let command = format_string!("{command}");
-        self.run_api_impl(&command, options, api_endpoint, api_path)
+        self.run_api_impl(&command, options, api_path, category)
             .await
     }
 
@@ -378,35 +666,142 @@ impl WeatherApi {
         self.run_geo("zip", &options).await
     }
 
+    async fn run_one_call<T: serde::de::DeserializeOwned>(
+        &self,
+        command: &str,
+        options: &[(&'static str, ApiStringType)],
+        category: CacheCategory,
+    ) -> Result<T, Error> {
+        let api_path = &self.one_call_path;
+        self.run_api_impl(command, options, api_path, category)
+            .await
+    }
+
     async fn run_geo<T: serde::de::DeserializeOwned>(
         &self,
         command: &str,
         options: &[(&'static str, ApiStringType)],
     ) -> Result<T, Error> {
-        let api_endpoint = &self.api_endpoint;
         let api_path = &self.geo_path;
-        self.run_api_impl(command, options, api_endpoint, api_path)
+        self.run_api_impl(command, options, api_path, CacheCategory::Geo)
             .await
     }
 
+    fn get_url(
+        &self,
+        command: &str,
+        options: &[(&'static str, ApiStringType)],
+        api_path: &str,
+    ) -> Result<Url, Error> {
+        let base_url = self.get_base_url()?;
+        let api_path = api_path.trim_start_matches('/');
+        let url = format!("{base_url}{api_path}{command}");
+        Url::parse_with_params(&url, options).map_err(Into::into)
+    }
+
     async fn run_api_impl<T: serde::de::DeserializeOwned>(
         &self,
         command: &str,
         options: &[(&'static str, ApiStringType)],
-        api_endpoint: &str,
         api_path: &str,
+        category: CacheCategory,
     ) -> Result<T, Error> {
-        let base_url = format!("https://{api_endpoint}/{api_path}{command}");
-        let url = Url::parse_with_params(&base_url, options)?;
-        self.client
-            .get(url)
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await
-            .map_err(Into::into)
+        let base_url = self.get_base_url()?;
+        let key = WeatherCache::get_key(base_url.as_str(), api_path, command, options);
+        if let Some(body) = self.cache.as_ref().and_then(|c| c.get(&key, category))
+            && let Ok(value) = serde_json::from_slice(&body)
+        {
+            return Ok(value);
+        }
+        let url = self.get_url(command, options, api_path)?;
+        let response = self.get_response(&url).await?;
+        if !response.is_success() {
+            return Err(ApiError::from_response(response.status, &response.body).into());
+        }
+        let value = serde_json::from_slice(&response.body).map_err(|e| {
+            ApiError::from_body(&response.body).map_or_else(|| Error::from(e), Into::into)
+        })?;
+        if let Some(cache) = &self.cache
+            && let Err(e) = cache.put(&key, &response.body)
+        {
+            warn!("failed to write cache {key}: {e}");
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(feature = "cli")]
+impl WeatherApi {
+    async fn get_response(&self, url: &Url) -> Result<TransportResponse, Error> {
+        let policy = &self.retry_policy;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let response = match self.transport.get(url).await {
+                Ok(response) => response,
+                Err(Error::ReqwestError(e)) if e.is_connect() || e.is_timeout() => {
+                    if attempts >= policy.max_attempts {
+                        return Err(retries_exhausted(attempts, None, e.into()));
+                    }
+                    warn!("request failed on attempt {attempts}: {e}");
+                    sleep(policy.get_delay(attempts, None)).await;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            if response.is_success() || !policy.is_retryable(response.status) {
+                return Ok(response);
+            }
+            if attempts >= policy.max_attempts {
+                let error = ApiError::from_response(response.status, &response.body).into();
+                return Err(retries_exhausted(attempts, Some(response.status), error));
+            }
+            sleep(policy.get_delay(attempts, response.retry_after)).await;
+        }
+    }
+}
+
+/// Wrap the error of the last attempt, a single attempt made without retries
+/// is returned as is.
+#[cfg(feature = "cli")]
+fn retries_exhausted(attempts: usize, status: Option<u16>, error: Error) -> Error {
+    if attempts > 1 {
+        Error::RetriesExhausted {
+            attempts,
+            status,
+            source: Box::new(error),
+        }
+    } else {
+        error
+    }
+}
+
+/// Parse and validate a base url, the url must use the `http` or `https` scheme,
+/// have a host and no query or fragment. A trailing `/` is appended to the
+/// path if missing so that api paths can be appended to it.
+#[cfg(feature = "cli")]
+fn parse_base_url(base_url: &str) -> Result<Url, Error> {
+    let mut url = Url::parse(base_url)?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(Error::InvalidInputError(format_string!(
+            "base url {base_url} must use http or https"
+        )));
+    }
+    if url.cannot_be_a_base() || url.host().is_none() {
+        return Err(Error::InvalidInputError(format_string!(
+            "base url {base_url} has no host"
+        )));
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err(Error::InvalidInputError(format_string!(
+            "base url {base_url} cannot have a query or fragment"
+        )));
+    }
+    if !url.path().ends_with('/') {
+        let path = format_string!("{}/", url.path());
+        url.set_path(&path);
     }
+    Ok(url)
 }
 
 #[cfg(test)]
@@ -423,7 +818,77 @@ mod tests {
     use crate::{ApiStringType, Error, weather_api::WeatherLocation};
 
     #[cfg(feature = "cli")]
-    use crate::weather_api::WeatherApi;
+    use futures::future::BoxFuture;
+    #[cfg(feature = "cli")]
+    use reqwest::{Client, Url};
+    #[cfg(feature = "cli")]
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+    #[cfg(feature = "cli")]
+    use tempfile::TempDir;
+    #[cfg(feature = "cli")]
+    use time::macros::{date, datetime};
+
+    #[cfg(feature = "cli")]
+    use crate::{
+        api_error::ApiError,
+        language::Language,
+        one_call::OneCallExclude,
+        retry_policy::RetryPolicy,
+        weather_api::WeatherApi,
+        weather_cache::WeatherCache,
+        weather_transport::{FixtureTransport, TransportResponse, WeatherTransport},
+    };
+
+    /// Transport which fails with a connection error before replaying
+    /// fixtures
+    #[cfg(feature = "cli")]
+    struct FlakyTransport {
+        failures: AtomicUsize,
+        fixtures: FixtureTransport,
+    }
+
+    #[cfg(feature = "cli")]
+    impl WeatherTransport for FlakyTransport {
+        fn get<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, Result<TransportResponse, Error>> {
+            Box::pin(async move {
+                if self
+                    .failures
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_ok()
+                {
+                    // nothing listens on port 1, so the connection is refused
+                    let client = Client::builder().no_proxy().build()?;
+                    client.get("http://127.0.0.1:1/").send().await?;
+                }
+                self.fixtures.get(url).await
+            })
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    fn get_fixture_transport() -> FixtureTransport {
+        FixtureTransport::new()
+            .with_fixture("weather", &[], include_str!("../tests/weather.json"))
+            .with_fixture("forecast", &[], include_str!("../tests/forecast.json"))
+            .with_fixture(
+                "geo/1.0/zip",
+                &[("zip", "11106,US")],
+                include_str!("../tests/geo_zip.json"),
+            )
+            .with_fixture(
+                "geo/1.0/direct",
+                &[("q", "Astoria,NY,US")],
+                include_str!("../tests/geo_direct.json"),
+            )
+            .with_fixture(
+                "geo/1.0/reverse",
+                &[],
+                include_str!("../tests/geo_reverse.json"),
+            )
+    }
 
     #[cfg(feature = "cli")]
     #[tokio::test]
@@ -471,6 +936,379 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_fixture_api() -> Result<(), Error> {
+        let transport = get_fixture_transport();
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport.clone());
+
+        let loc = WeatherLocation::from_zipcode(11106)
+            .to_lat_lon(&api)
+            .await?;
+        if let WeatherLocation::LatLon {
+            latitude,
+            longitude,
+        } = loc
+        {
+            let lat: f64 = latitude.into();
+            let lon: f64 = longitude.into();
+            assert!((lat - 40.76080).abs() < 0.00001);
+            assert!((lon - -73.92950).abs() < 0.00001);
+
+            let locations = api.get_geo_location(latitude, longitude).await?;
+            assert_eq!(locations.len(), 1);
+            assert_eq!(&locations[0].name, "New York");
+        } else {
+            panic!("expected LatLon");
+        }
+
+        let locations = api.get_direct_location("Astoria,NY,US").await?;
+        assert_eq!(locations.len(), 2);
+        assert_eq!(&locations[0].name, "Astoria");
+        assert_eq!(
+            locations[1].to_string(),
+            "Astoria, Oregon, US 46.18788N -123.83125E"
+        );
+
+        let (data, forecast) =
+            join(api.get_weather_data(&loc), api.get_weather_forecast(&loc)).await;
+        let (data, forecast) = (data?, forecast?);
+        assert_eq!(&data.name, "Astoria");
+        assert_eq!(forecast.city.timezone.into_inner(), -18000);
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 5);
+        assert_eq!(
+            requests[0].as_str(),
+            "https://test.local/geo/1.0/zip?appid=8675309&zip=11106%2CUS"
+        );
+        let zip_location = api.get_zip_location(11106, None).await?;
+        assert_eq!(
+            zip_location.to_string(),
+            "11106 New York, US 40.76080N -73.92950E"
+        );
+
+        let result = api.get_direct_location("Springfield").await;
+        assert!(matches!(result, Err(Error::LocationNotFound(_))));
+
+        let api = api.with_transport(FixtureTransport::new().with_response(
+            "weather",
+            &[],
+            TransportResponse::new(401, r#"{"cod":401,"message":"Invalid API key."}"#),
+        ));
+        let result = api.get_weather_data(&loc).await;
+        assert!(matches!(result, Err(Error::InvalidApiKey(m)) if &m == "Invalid API key."));
+
+        let api = api.with_transport(FixtureTransport::new().with_fixture(
+            "forecast",
+            &[],
+            r#"{"cod":"404","message":"city not found"}"#,
+        ));
+        let result = api.get_weather_forecast(&loc).await;
+        assert!(matches!(result, Err(Error::LocationNotFound(m)) if &m == "city not found"));
+
+        let api = api
+            .with_transport(FixtureTransport::new().with_response(
+                "weather",
+                &[],
+                TransportResponse::new(502, ""),
+            ))
+            .with_retry_policy(RetryPolicy::no_retry());
+        let result = api.get_weather_data(&loc).await;
+        assert!(matches!(result, Err(Error::ServerError(e)) if e.code == 502));
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_retry() -> Result<(), Error> {
+        let policy = RetryPolicy::default()
+            .with_base_delay(Duration::from_millis(1))
+            .with_jitter(false);
+        let transport = FixtureTransport::new().with_responses(
+            "weather",
+            &[],
+            vec![
+                TransportResponse::new(502, ""),
+                TransportResponse::new(429, "").with_retry_after(Duration::from_millis(1)),
+                TransportResponse::new(200, include_str!("../tests/weather.json")),
+            ],
+        );
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport.clone())
+            .with_retry_policy(policy.clone());
+        let loc = WeatherLocation::from_zipcode(11106);
+        let data = api.get_weather_data(&loc).await?;
+        assert_eq!(&data.name, "Astoria");
+        assert_eq!(transport.requests().len(), 3);
+
+        let transport = FixtureTransport::new()
+            .with_response("weather", &[], TransportResponse::new(503, ""))
+            .with_response("forecast", &[], TransportResponse::new(401, ""));
+        let api = api
+            .with_transport(transport.clone())
+            .with_retry_policy(policy.clone().with_max_attempts(4));
+        let result = api.get_weather_data(&loc).await;
+        assert!(matches!(
+            result,
+            Err(Error::RetriesExhausted {
+                attempts: 4,
+                status: Some(503),
+                source,
+            }) if matches!(*source, Error::ServerError(ApiError { code: 503, .. }))
+        ));
+        let result = api.get_weather_forecast(&loc).await;
+        assert!(matches!(result, Err(Error::InvalidApiKey(_))));
+        assert_eq!(transport.requests().len(), 5);
+
+        let transport = FixtureTransport::new().with_response(
+            "weather",
+            &[],
+            TransportResponse::new(
+                429,
+                r#"{"cod":429,"message":"Your account is temporary blocked"}"#,
+            ),
+        );
+        let api = api
+            .with_transport(transport.clone())
+            .with_retry_policy(policy.with_max_attempts(2));
+        let result = api.get_weather_data(&loc).await;
+        assert!(matches!(
+            result,
+            Err(Error::RetriesExhausted {
+                attempts: 2,
+                status: Some(429),
+                source,
+            }) if matches!(&*source, Error::RateLimited(m) if m == "Your account is temporary blocked")
+        ));
+        assert_eq!(transport.requests().len(), 2);
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_retries_exhausted() -> Result<(), Error> {
+        let transport = FixtureTransport::new().with_responses(
+            "weather",
+            &[],
+            vec![
+                TransportResponse::new(503, ""),
+                TransportResponse::new(503, ""),
+                TransportResponse::new(503, r#"{"cod":503,"message":"Service Unavailable"}"#),
+            ],
+        );
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport.clone())
+            .with_retry_policy(
+                RetryPolicy::default()
+                    .with_max_attempts(3)
+                    .with_base_delay(Duration::from_millis(1))
+                    .with_jitter(false),
+            );
+        let loc = WeatherLocation::from_zipcode(11106);
+        let error = api.get_weather_data(&loc).await.unwrap_err();
+        assert_eq!(transport.requests().len(), 3);
+        assert_eq!(
+            error.to_string(),
+            "Retries Exhausted after 3 attempts, last status 503: Server Error 503 Service \
+             Unavailable"
+        );
+        assert_eq!(
+            error.help_message(),
+            Some("openweathermap.org is unavailable, please try again later")
+        );
+        let Error::RetriesExhausted {
+            attempts,
+            status,
+            source,
+        } = error
+        else {
+            panic!("expected RetriesExhausted");
+        };
+        assert_eq!(attempts, 3);
+        assert_eq!(status, Some(503));
+        assert!(matches!(*source, Error::ServerError(e) if &e.message == "Service Unavailable"));
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_retry_connection_error() -> Result<(), Error> {
+        let policy = RetryPolicy::default()
+            .with_base_delay(Duration::from_millis(1))
+            .with_jitter(false);
+        let fixtures = get_fixture_transport();
+        let transport = FlakyTransport {
+            failures: AtomicUsize::new(2),
+            fixtures: fixtures.clone(),
+        };
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport)
+            .with_retry_policy(policy.clone());
+        let loc = WeatherLocation::from_zipcode(11106);
+        let data = api.get_weather_data(&loc).await?;
+        assert_eq!(&data.name, "Astoria");
+        assert_eq!(fixtures.requests().len(), 1);
+
+        let transport = FlakyTransport {
+            failures: AtomicUsize::new(2),
+            fixtures: fixtures.clone(),
+        };
+        let api = api
+            .with_transport(transport)
+            .with_retry_policy(policy.with_max_attempts(2));
+        let result = api.get_weather_data(&loc).await;
+        assert!(matches!(
+            result,
+            Err(Error::RetriesExhausted {
+                attempts: 2,
+                status: None,
+                source,
+            }) if matches!(&*source, Error::ReqwestError(e) if e.is_connect())
+        ));
+        assert_eq!(fixtures.requests().len(), 1);
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_one_call() -> Result<(), Error> {
+        let transport = get_fixture_transport().with_fixture(
+            "data/3.0/onecall",
+            &[],
+            include_str!("../tests/onecall.json"),
+        );
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport.clone());
+        let loc = WeatherLocation::from_zipcode(11106);
+        let data = api
+            .get_one_call(&loc, &[OneCallExclude::Minutely, OneCallExclude::Hourly])
+            .await?;
+        assert_eq!(&data.timezone, "America/New_York");
+        assert_eq!(data.daily.map(|d| d.len()), Some(2));
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[1].as_str(),
+            "https://test.local/data/3.0/onecall?lat=40.76080&lon=-73.92950&appid=8675309&\
+             exclude=minutely%2Chourly"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_historical() -> Result<(), Error> {
+        let transport = get_fixture_transport()
+            .with_fixture(
+                "onecall/timemachine",
+                &[("dt", "1710417600")],
+                include_str!("../tests/onecall_timemachine.json"),
+            )
+            .with_fixture(
+                "onecall/day_summary",
+                &[("date", "2024-03-14")],
+                include_str!("../tests/onecall_day_summary.json"),
+            );
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport.clone());
+        let loc = WeatherLocation::from_zipcode(11106);
+
+        let data = api
+            .get_historical(&loc, datetime!(2024-03-14 12:00:00 UTC))
+            .await?;
+        assert_eq!(data.data.len(), 1);
+        let data = api.get_day_summary(&loc, date!(2024 - 03 - 14)).await?;
+        assert_eq!(data.date, date!(2024 - 03 - 14));
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(
+            requests[1].as_str(),
+            "https://test.local/data/3.0/onecall/timemachine?lat=40.76080&lon=-73.92950&\
+             appid=8675309&dt=1710417600"
+        );
+        assert_eq!(
+            requests[3].as_str(),
+            "https://test.local/data/3.0/onecall/day_summary?lat=40.76080&lon=-73.92950&\
+             appid=8675309&date=2024-03-14"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_air_pollution() -> Result<(), Error> {
+        let transport = get_fixture_transport()
+            .with_fixture(
+                "air_pollution",
+                &[],
+                include_str!("../tests/air_pollution.json"),
+            )
+            .with_fixture(
+                "air_pollution/forecast",
+                &[],
+                include_str!("../tests/air_pollution_forecast.json"),
+            )
+            .with_fixture(
+                "air_pollution/history",
+                &[("start", "1710374400"), ("end", "1710460800")],
+                include_str!("../tests/air_pollution_forecast.json"),
+            );
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport.clone());
+        let loc = WeatherLocation::from_zipcode(11106);
+
+        let current = api.get_air_pollution(&loc).await?;
+        assert_eq!(current.list.len(), 1);
+        let forecast = api.get_air_pollution_forecast(&loc).await?;
+        assert_eq!(forecast.list.len(), 3);
+        let history = api
+            .get_air_pollution_history(
+                &loc,
+                datetime!(2024-03-14 00:00:00 UTC),
+                datetime!(2024-03-15 00:00:00 UTC),
+            )
+            .await?;
+        assert_eq!(history, forecast);
+
+        let requests = transport.requests();
+        assert_eq!(
+            requests[1].as_str(),
+            "https://test.local/data/2.5/air_pollution?lat=40.76080&lon=-73.92950&appid=8675309"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_cached_api() -> Result<(), Error> {
+        let dir = TempDir::new()?;
+        let cache = WeatherCache::new(dir.path());
+        let transport = get_fixture_transport();
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport.clone())
+            .with_cache(cache.clone());
+        let loc = WeatherLocation::from_zipcode(11106);
+
+        let data = api.get_weather_data(&loc).await?;
+        let cached = api.get_weather_data(&loc).await?;
+        assert_eq!(data, cached);
+        let loc = loc.to_lat_lon(&api).await?;
+        loc.to_lat_lon(&api).await?;
+        assert_eq!(transport.requests().len(), 2);
+
+        let api = api.with_cache(cache.with_refresh(true));
+        let refreshed = api
+            .get_weather_data(&WeatherLocation::from_zipcode(11106))
+            .await?;
+        assert_eq!(data, refreshed);
+        assert_eq!(transport.requests().len(), 3);
+        Ok(())
+    }
+
     #[cfg(feature = "cli")]
     #[tokio::test]
     async fn test_process_opts() -> Result<(), Error> {
@@ -565,6 +1403,66 @@ mod tests {
             ("appid", "8675309".into()),
         ];
         assert_eq!(opts, expected);
+
+        let api = api.with_language(Language::De);
+        let loc = WeatherLocation::from_city_name("Berlin");
+        let opts = api.get_options(&loc);
+        let expected: Vec<(&str, ApiStringType)> = vec![
+            ("q", "Berlin".into()),
+            ("appid", "8675309".into()),
+            ("lang", "de".into()),
+        ];
+        assert_eq!(opts, expected);
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_base_url() -> Result<(), Error> {
+        let api = WeatherApi::new("8675309", "api.openweathermap.org", "data/2.5/", "geo/1.0/");
+        let options = vec![("q", "New York".into())];
+        let url = api.get_url("weather", &options, "data/2.5/")?;
+        assert_eq!(
+            url.as_str(),
+            "https://api.openweathermap.org/data/2.5/weather?q=New+York"
+        );
+
+        let api = api.with_base_url("http://localhost:8080/owm")?;
+        assert_eq!(
+            format!("{api:?}"),
+            "WeatherApi(key=8675309,endpoint=http://localhost:8080/owm/)".to_string()
+        );
+        let url = api.get_url("weather", &options, "data/2.5/")?;
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:8080/owm/data/2.5/weather?q=New+York"
+        );
+        let url = api.get_url("zip", &options, "/geo/1.0/")?;
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:8080/owm/geo/1.0/zip?q=New+York"
+        );
+        assert_ne!(
+            api,
+            WeatherApi::new("8675309", "api.openweathermap.org", "data/2.5/", "geo/1.0/")
+        );
+
+        assert!(
+            WeatherApi::default()
+                .with_base_url("ftp://localhost/")
+                .is_err()
+        );
+        assert!(
+            WeatherApi::default()
+                .with_base_url("localhost:8080")
+                .is_err()
+        );
+        assert!(
+            WeatherApi::default()
+                .with_base_url("http://localhost/?q=1")
+                .is_err()
+        );
+        assert!(WeatherApi::default().with_base_url("not a url").is_err());
         Ok(())
     }
 
@@ -0,0 +1,281 @@
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{ApiStringType, Error, StringType, format_string};
+
+const DEFAULT_WEATHER_TTL: Duration = Duration::from_secs(10 * 60);
+const DEFAULT_FORECAST_TTL: Duration = Duration::from_secs(60 * 60);
+const DEFAULT_GEO_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// Longest key used as is, file names are limited to 255 bytes on most
+/// filesystems
+const MAX_KEY_LENGTH: usize = 200;
+
+/// Kind of api response, determines which ttl applies to a cached response
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CacheCategory {
+    Weather,
+    Forecast,
+    Geo,
+}
+
+/// On-disk cache of raw api responses, by default stored under
+/// `${CACHE_DIR}/weather_util/`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeatherCache {
+    directory: PathBuf,
+    weather_ttl: Duration,
+    forecast_ttl: Duration,
+    geo_ttl: Duration,
+    refresh: bool,
+}
+
+impl WeatherCache {
+    #[must_use]
+    pub fn new(directory: &Path) -> Self {
+        Self {
+            directory: directory.to_path_buf(),
+            weather_ttl: DEFAULT_WEATHER_TTL,
+            forecast_ttl: DEFAULT_FORECAST_TTL,
+            geo_ttl: DEFAULT_GEO_TTL,
+            refresh: false,
+        }
+    }
+
+    /// `${CACHE_DIR}/weather_util/`, where `CACHE_DIR` is determined by
+    /// `dirs::cache_dir`
+    #[must_use]
+    pub fn default_directory() -> Option<PathBuf> {
+        dirs::cache_dir().map(|d| d.join("weather_util"))
+    }
+
+    #[must_use]
+    pub fn with_weather_ttl(self, weather_ttl: Duration) -> Self {
+        Self {
+            weather_ttl,
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_forecast_ttl(self, forecast_ttl: Duration) -> Self {
+        Self {
+            forecast_ttl,
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_geo_ttl(self, geo_ttl: Duration) -> Self {
+        Self { geo_ttl, ..self }
+    }
+
+    /// Ignore cached responses, but still store new ones
+    #[must_use]
+    pub fn with_refresh(self, refresh: bool) -> Self {
+        Self { refresh, ..self }
+    }
+
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    #[must_use]
+    pub fn get_ttl(&self, category: CacheCategory) -> Duration {
+        match category {
+            CacheCategory::Weather => self.weather_ttl,
+            CacheCategory::Forecast => self.forecast_ttl,
+            CacheCategory::Geo => self.geo_ttl,
+        }
+    }
+
+    /// Cache key for a request, the api key (`appid`) and the scheme of
+    /// `base_url` are not part of the key.  Characters other than ascii
+    /// alphanumerics, `-` and `.` are escaped so that the key can be used as a
+    /// file name, keys longer than `MAX_KEY_LENGTH` are truncated and suffixed
+    /// with a hash of the full key.
+    /// ```
+    /// use weather_util_rust::weather_cache::WeatherCache;
+    /// let options = vec![("q", "New York".into()), ("appid", "1234".into())];
+    /// let key = WeatherCache::get_key(
+    ///     "https://api.openweathermap.org/",
+    ///     "data/2.5/",
+    ///     "weather",
+    ///     &options,
+    /// );
+    /// assert_eq!(
+    ///     &key,
+    ///     "api.openweathermap.org_2fdata_2f2.5_2fweather_26q_3dNew_20York"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn get_key(
+        base_url: &str,
+        api_path: &str,
+        command: &str,
+        options: &[(&'static str, ApiStringType)],
+    ) -> StringType {
+        let base_url = base_url
+            .split_once("://")
+            .map_or(base_url, |(_, rest)| rest);
+        let mut key = StringType::new();
+        let mut escape = |s: &str| {
+            for c in s.chars() {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                    key.push(c);
+                } else {
+                    let mut buf = [0; 4];
+                    for b in c.encode_utf8(&mut buf).bytes() {
+                        write!(key, "_{b:02x}").unwrap_or(());
+                    }
+                }
+            }
+        };
+        escape(base_url);
+        escape(api_path.trim_start_matches('/'));
+        escape(command);
+        for (k, v) in options {
+            if *k == "appid" {
+                continue;
+            }
+            escape("&");
+            escape(k);
+            escape("=");
+            escape(v);
+        }
+        if key.len() > MAX_KEY_LENGTH {
+            // escaped keys are ascii, so any index is a char boundary
+            let hash = fnv1a_hash(key.as_bytes());
+            let prefix = &key[..MAX_KEY_LENGTH - 17];
+            key = format_string!("{prefix}_{hash:016x}");
+        }
+        key
+    }
+
+    fn get_path(&self, key: &str) -> PathBuf {
+        self.directory.join(format!("{key}.json"))
+    }
+
+    /// Return cached response for `key` if it exists and is younger than the
+    /// ttl for `category`
+    #[must_use]
+    pub fn get(&self, key: &str, category: CacheCategory) -> Option<Vec<u8>> {
+        if self.refresh {
+            return None;
+        }
+        let path = self.get_path(key);
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+        let age = SystemTime::now().duration_since(modified).ok()?;
+        if age < self.get_ttl(category) {
+            fs::read(&path).ok()
+        } else {
+            None
+        }
+    }
+
+    /// Store response for `key`
+    /// # Errors
+    ///
+    /// Will return error if the cache directory cannot be created or the file
+    /// cannot be written
+    pub fn put(&self, key: &str, body: &[u8]) -> Result<(), Error> {
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.get_path(key), body)?;
+        Ok(())
+    }
+}
+
+/// 64 bit FNV-1a, unlike `DefaultHasher` the result is stable across
+/// releases, so cached files stay valid
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    use crate::{
+        ApiStringType, Error,
+        weather_cache::{CacheCategory, MAX_KEY_LENGTH, WeatherCache},
+    };
+
+    const BASE_URL: &str = "https://api.openweathermap.org/";
+
+    #[test]
+    fn test_get_key() {
+        let options: Vec<(&str, ApiStringType)> = vec![
+            ("zip", "10001".into()),
+            ("country_code", "US".into()),
+            ("appid", "8675309".into()),
+        ];
+        let key = WeatherCache::get_key(BASE_URL, "data/2.5/", "forecast", &options);
+        assert_eq!(
+            &key,
+            "api.openweathermap.org_2fdata_2f2.5_2fforecast_26zip_3d10001_26country_5fcode_3dUS"
+        );
+        let options: Vec<(&str, ApiStringType)> = vec![("q", "New_York".into())];
+        let key0 = WeatherCache::get_key(BASE_URL, "data/2.5/", "weather", &options);
+        let options: Vec<(&str, ApiStringType)> = vec![("q", "New York".into())];
+        let key1 = WeatherCache::get_key(BASE_URL, "data/2.5/", "weather", &options);
+        assert_ne!(key0, key1);
+        let key2 =
+            WeatherCache::get_key("http://localhost:8080/", "data/2.5/", "weather", &options);
+        assert_eq!(
+            &key2,
+            "localhost_3a8080_2fdata_2f2.5_2fweather_26q_3dNew_20York"
+        );
+        assert_ne!(key1, key2);
+    }
+
+    #[test]
+    fn test_get_key_long() {
+        let city = "São Paulo, ".repeat(20);
+        let options: Vec<(&str, ApiStringType)> = vec![("q", city.as_str().into())];
+        let key0 = WeatherCache::get_key(BASE_URL, "data/2.5/", "weather", &options);
+        assert_eq!(key0.len(), MAX_KEY_LENGTH);
+        assert!(key0.starts_with("api.openweathermap.org_2fdata_2f2.5_2fweather_26q_3dS"));
+        let city = format!("{city}!");
+        let options: Vec<(&str, ApiStringType)> = vec![("q", city.as_str().into())];
+        let key1 = WeatherCache::get_key(BASE_URL, "data/2.5/", "weather", &options);
+        assert_eq!(key1.len(), MAX_KEY_LENGTH);
+        assert_ne!(key0, key1);
+    }
+
+    #[test]
+    fn test_weather_cache() -> Result<(), Error> {
+        let dir = TempDir::new()?;
+        let cache_dir = dir.path().join("weather_util");
+        let cache = WeatherCache::new(&cache_dir);
+        assert_eq!(
+            cache.get_ttl(CacheCategory::Weather),
+            Duration::from_secs(600)
+        );
+        assert_eq!(cache.get("key", CacheCategory::Weather), None);
+
+        cache.put("key", b"{}")?;
+        assert_eq!(
+            cache.get("key", CacheCategory::Weather),
+            Some(b"{}".to_vec())
+        );
+        assert_eq!(cache.get("key", CacheCategory::Geo), Some(b"{}".to_vec()));
+
+        let cache = cache.with_weather_ttl(Duration::from_secs(0));
+        assert_eq!(cache.get("key", CacheCategory::Weather), None);
+        assert_eq!(
+            cache.get("key", CacheCategory::Forecast),
+            Some(b"{}".to_vec())
+        );
+
+        let cache = cache.with_refresh(true);
+        assert_eq!(cache.get("key", CacheCategory::Forecast), None);
+        Ok(())
+    }
+}
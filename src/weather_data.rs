@@ -3,9 +3,11 @@ use std::fmt::Write;
 use time::{OffsetDateTime, UtcOffset};
 
 use crate::{
-    StringType, default_datetime, direction::Direction, distance::Distance, humidity::Humidity,
-    latitude::Latitude, longitude::Longitude, precipitation::Precipitation, pressure::Pressure,
-    speed::Speed, temperature::Temperature, timestamp, timezone::TimeZone,
+    StringType, default_datetime, derived::DerivedConditions, direction::Direction,
+    distance::Distance, format_string, humidity::Humidity, language::Message, latitude::Latitude,
+    longitude::Longitude, precipitation::Precipitation, pressure::Pressure, speed::Speed,
+    temperature::Temperature, text_options::TextOptions, timestamp, timezone::TimeZone,
+    weather_icon::WeatherIcon,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -22,6 +24,13 @@ pub struct WeatherCond {
     pub icon: StringType,
 }
 
+impl WeatherCond {
+    #[must_use]
+    pub fn get_icon(&self) -> Option<WeatherIcon> {
+        WeatherIcon::from_condition(self.id, &self.icon)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
 pub struct WeatherMain {
     pub temp: Temperature,
@@ -30,6 +39,10 @@ pub struct WeatherMain {
     pub temp_max: Temperature,
     pub pressure: Pressure,
     pub humidity: Humidity,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sea_level: Option<Pressure>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grnd_level: Option<Pressure>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
@@ -37,10 +50,24 @@ pub struct Wind {
     pub speed: Speed,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deg: Option<Direction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gust: Option<Speed>,
+}
+
+/// Cloudiness in percent
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Clouds {
+    pub all: i64,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct Sys {
+    /// Internal openweathermap.org parameter
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub sys_type: Option<i64>,
+    /// Internal openweathermap.org parameter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub country: Option<StringType>,
     #[serde(with = "timestamp")]
@@ -52,6 +79,8 @@ pub struct Sys {
 impl Default for Sys {
     fn default() -> Self {
         Self {
+            sys_type: None,
+            id: None,
             country: None,
             sunrise: default_datetime(),
             sunset: default_datetime(),
@@ -61,17 +90,33 @@ impl Default for Sys {
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 pub struct Rain {
-    #[serde(alias = "3h", skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename(deserialize = "3h"),
+        alias = "three_hour",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub three_hour: Option<Precipitation>,
-    #[serde(alias = "1h", skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename(deserialize = "1h"),
+        alias = "one_hour",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub one_hour: Option<Precipitation>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 pub struct Snow {
-    #[serde(alias = "3h", skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename(deserialize = "3h"),
+        alias = "three_hour",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub three_hour: Option<Precipitation>,
-    #[serde(alias = "1h", skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename(deserialize = "1h"),
+        alias = "one_hour",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub one_hour: Option<Precipitation>,
 }
 
@@ -88,11 +133,19 @@ pub struct WeatherData {
     pub rain: Option<Rain>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub snow: Option<Snow>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clouds: Option<Clouds>,
     #[serde(with = "timestamp")]
     pub dt: OffsetDateTime,
     pub sys: Sys,
     pub timezone: TimeZone,
+    /// City id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
     pub name: StringType,
+    /// Internal openweathermap.org parameter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cod: Option<i64>,
 }
 
 impl Default for WeatherData {
@@ -106,15 +159,24 @@ impl Default for WeatherData {
             wind: Wind::default(),
             rain: None,
             snow: None,
+            clouds: None,
             dt: default_datetime(),
             sys: Sys::default(),
             timezone: TimeZone::default(),
+            id: None,
             name: "".into(),
+            cod: None,
         }
     }
 }
 
 impl WeatherData {
+    /// Dew point, heat index, wind chill etc. for the current conditions
+    #[must_use]
+    pub fn get_derived(&self) -> DerivedConditions {
+        DerivedConditions::new(self.main.temp, self.main.humidity, Some(self.wind.speed))
+    }
+
     #[must_use]
     pub fn get_offset(&self) -> UtcOffset {
         self.timezone.into()
@@ -138,7 +200,7 @@ impl WeatherData {
     /// Write out formatted information about current conditions for a mutable
     /// buffer.
     /// ```
-    /// use weather_util_rust::weather_data::WeatherData;
+    /// use weather_util_rust::{text_options::TextOptions, weather_data::WeatherData};
     /// # use anyhow::Error;
     /// # use std::io::{stdout, Write, Read};
     /// # use std::fs::File;
@@ -148,7 +210,7 @@ impl WeatherData {
     /// # f.read_to_string(&mut buf)?;
     /// let data: WeatherData = serde_json::from_str(&buf)?;
     ///
-    /// let buf = data.get_current_conditions();
+    /// let buf = data.get_current_conditions(TextOptions::default());
     ///
     /// assert!(buf.starts_with("Current conditions Astoria US 40.76"));
     /// assert!(buf.contains("Temperature: 38.50 F (3.61 C)"));
@@ -156,8 +218,11 @@ impl WeatherData {
     /// # }
     /// ```
     #[must_use]
-    pub fn get_current_conditions(&self) -> StringType {
-        let mut output: StringType = "Current conditions ".into();
+    pub fn get_current_conditions(&self, options: TextOptions) -> StringType {
+        let TextOptions {
+            units, icons, lang, ..
+        } = options;
+        let mut output = format_string!("{} ", lang.get(Message::CurrentConditions));
         let fo: UtcOffset = self.timezone.into();
         let dt = self.dt.to_offset(fo);
         let sunrise = self.sys.sunrise.to_offset(fo);
@@ -166,46 +231,80 @@ impl WeatherData {
             let name = &self.name;
             write!(output, "{name} {country} ").unwrap_or(());
         }
-        writeln!(output, "{:0.5}N {:0.5}E", self.coord.lat, self.coord.lon).unwrap_or(());
-        writeln!(output, "Last Updated {dt}").unwrap_or(());
+        let lat_lon = format_string!("{:0.5}N {:0.5}E", self.coord.lat, self.coord.lon);
+        writeln!(output, "{}", lang.localize_decimal(&lat_lon)).unwrap_or(());
+        writeln!(
+            output,
+            "{} {}",
+            lang.get(Message::LastUpdated),
+            lang.format_timestamp(dt)
+        )
+        .unwrap_or(());
+        writeln!(
+            output,
+            "\t{}: {}",
+            lang.get(Message::Temperature),
+            lang.localize_decimal(&units.format_temperature(self.main.temp, 2))
+        )
+        .unwrap_or(());
         writeln!(
             output,
-            "\tTemperature: {f:0.2} F ({c:0.2} C)",
-            f = self.main.temp.fahrenheit(),
-            c = self.main.temp.celcius(),
+            "\t{}: {}%",
+            lang.get(Message::RelativeHumidity),
+            self.main.humidity
         )
         .unwrap_or(());
-        writeln!(output, "\tRelative Humidity: {}%", self.main.humidity).unwrap_or(());
         writeln!(
             output,
-            "\tWind: {d} degrees at {s:0.2} mph",
-            d = self.wind.deg.unwrap_or_else(|| 0.0.into()),
-            s = self.wind.speed.mph(),
+            "\t{}: {}",
+            lang.get(Message::Pressure),
+            lang.localize_decimal(&units.format_pressure(self.main.pressure, 0))
         )
         .unwrap_or(());
+        output.push_str(&self.get_derived().get_conditions(units, lang));
         writeln!(
             output,
-            "\tConditions: {}",
+            "\t{}: {}",
+            lang.get(Message::Wind),
+            units.format_wind(self.wind.speed, self.wind.deg, self.wind.gust, lang)
+        )
+        .unwrap_or(());
+        writeln!(
+            output,
+            "\t{}: {}{}",
+            lang.get(Message::Conditions),
+            icons.prefix(self.weather.first().and_then(WeatherCond::get_icon)),
             self.weather.first().map_or_else(|| "", |w| &w.description)
         )
         .unwrap_or(());
-        writeln!(output, "\tSunrise: {sunrise}").unwrap_or(());
-        write!(output, "\tSunset: {sunset}").unwrap_or(());
-        if let Some(rain) = &self.rain {
-            write!(
-                output,
-                "\n\tRain: {:0.3} in",
-                rain.one_hour.map_or(0.0, Precipitation::inches)
-            )
-            .unwrap_or(());
-        }
-        if let Some(snow) = &self.snow {
-            write!(
-                output,
-                "\n\tSnow: {:0.3} in",
-                snow.one_hour.map_or(0.0, Precipitation::inches)
-            )
-            .unwrap_or(());
+        writeln!(
+            output,
+            "\t{}: {}",
+            lang.get(Message::Sunrise),
+            lang.format_timestamp(sunrise)
+        )
+        .unwrap_or(());
+        write!(
+            output,
+            "\t{}: {}",
+            lang.get(Message::Sunset),
+            lang.format_timestamp(sunset)
+        )
+        .unwrap_or(());
+        for (label, precip) in [
+            (Message::Rain, self.rain.map(|r| r.one_hour)),
+            (Message::Snow, self.snow.map(|s| s.one_hour)),
+        ] {
+            if let Some(precip) = precip {
+                let precip = units.format_precipitation(precip.unwrap_or_default(), 3);
+                write!(
+                    output,
+                    "\n\t{}: {}",
+                    lang.get(label),
+                    lang.localize_decimal(&precip)
+                )
+                .unwrap_or(());
+            }
         }
         output.push('\n');
         output
@@ -214,10 +313,16 @@ impl WeatherData {
 
 #[cfg(test)]
 mod test {
+    use approx::assert_abs_diff_eq;
+
     use crate::{
         Error, default_datetime,
+        language::Language,
+        text_options::TextOptions,
         timezone::TimeZone,
+        unit_system::{PressureUnit, UnitSystem, Units},
         weather_data::{Coord, Sys, WeatherData, WeatherMain, Wind},
+        weather_icon::{IconStyle, WeatherIcon},
     };
     use log::info;
 
@@ -226,14 +331,84 @@ mod test {
         let buf = include_str!("../tests/weather.json");
         let data: WeatherData = serde_json::from_str(buf)?;
 
-        let buf = data.get_current_conditions();
+        let buf = data.get_current_conditions(TextOptions::default());
 
         assert!(buf.starts_with("Current conditions Astoria US 40.76"));
         assert!(buf.contains("Temperature: 38.50 F (3.61 C)"));
+        assert!(buf.contains("\tWind: N 12 mph, Beaufort 3 gentle breeze\n"));
+        assert!(buf.contains("\tConditions: broken clouds\n"));
+        assert!(buf.contains("\tPressure: 1015 hPa\n"));
+
+        assert_eq!(data.weather[0].get_icon(), Some(WeatherIcon::BrokenClouds));
+        let buf = data.get_current_conditions(TextOptions::default().with_icons(IconStyle::Ascii));
+        assert!(buf.contains("\tConditions: ~~~ broken clouds\n"));
+        let buf = data.get_current_conditions(TextOptions::default().with_icons(IconStyle::Emoji));
+        assert!(buf.contains("\tConditions: ☁️ broken clouds\n"));
+
+        let buf =
+            data.get_current_conditions(TextOptions::default().with_units(Units::Metric.into()));
+        assert!(buf.contains("Temperature: 3.61 °C"));
+        assert!(buf.contains("\tWind: N 19 km/h, Beaufort 3 gentle breeze\n"));
+        assert!(buf.contains("\tDew Point: -8.44 °C\n"));
+        assert!(buf.contains("\tWind Chill: "));
+        assert!(!buf.contains("Heat Index"));
+        assert!(!buf.contains(" F"));
+
+        let gusty = include_str!("../tests/weather.json")
+            .replace(r#""deg":0.0}"#, r#""deg":300.0,"gust":9.1}"#);
+        let gusty: WeatherData = serde_json::from_str(&gusty)?;
+        assert_abs_diff_eq!(gusty.wind.gust.unwrap().mps(), 9.1);
+        let buf = gusty.get_current_conditions(TextOptions::default());
+        assert!(buf.contains("\tWind: WNW 12 mph, gusts 20 mph, Beaufort 3 gentle breeze\n"));
+
+        let buf = data.get_current_conditions(
+            TextOptions::default()
+                .with_units(Units::Metric.into())
+                .with_lang(Language::De),
+        );
+        assert!(buf.starts_with("Aktuelles Wetter Astoria US 40,76"));
+        assert!(buf.contains("\tTemperatur: 3,61 °C\n"));
+        assert!(buf.contains("\tTaupunkt: -8,44 °C\n"));
+        assert!(buf.contains("\tWind: N 19 km/h, Beaufort 3 schwache Brise\n"));
+        assert!(buf.contains("\tSonnenaufgang: 27.02.2022 06:32 -05:00\n"));
+
+        let units = UnitSystem::default().with_pressure(PressureUnit::Inhg);
+        let buf = data.get_current_conditions(
+            TextOptions::default()
+                .with_units(units)
+                .with_lang(Language::De),
+        );
+        assert!(buf.contains("\tLuftdruck: 29,97 inHg\n"));
+
+        let derived = data.get_derived();
+        assert!(derived.wind_chill.unwrap().celcius() < data.main.temp.celcius());
+        assert_eq!(derived.humidex, None);
         info!("{} {} {}", buf.len(), data.name, data.name.len());
         Ok(())
     }
 
+    #[test]
+    fn test_weather_data_round_trip() -> Result<(), Error> {
+        let buf = include_str!("../tests/weather_full.json");
+        let data: WeatherData = serde_json::from_str(buf)?;
+        assert_eq!(data.id, Some(5106292));
+        assert_eq!(data.cod, Some(200));
+        assert_eq!(data.clouds.unwrap().all, 100);
+        assert_eq!(data.sys.sys_type, Some(2));
+        assert_eq!(data.sys.id, Some(2039034));
+        assert_abs_diff_eq!(data.main.grnd_level.unwrap().hpa(), 1007.0);
+        assert_abs_diff_eq!(data.wind.gust.unwrap().mps(), 10.8);
+        assert_eq!(data.weather.len(), 2);
+
+        // rain and snow keep serializing as `one_hour` and `three_hour`
+        let expected: serde_json::Value =
+            serde_json::from_str(&buf.replace(r#""1h""#, r#""one_hour""#))?;
+        assert_eq!(serde_json::to_value(&data)?, expected);
+        let round_trip: WeatherData = serde_json::from_str(&serde_json::to_string(&data)?)?;
+        assert_eq!(round_trip, data);
+        Ok(())
+    }
+
     #[test]
     fn test_default_sys() -> Result<(), Error> {
         let default_sys = Sys::default();
@@ -273,7 +448,7 @@ mod test {
             Sys::default().sunset.to_offset(default_offset)
         );
 
-        let conditions = default_data.get_current_conditions();
+        let conditions = default_data.get_current_conditions(TextOptions::default());
         assert!(conditions.contains("Relative Humidity: 0%"));
         Ok(())
     }
@@ -3,19 +3,30 @@ use std::{
     collections::{BTreeMap, BTreeSet},
     fmt::Write,
 };
-use time::{Date, OffsetDateTime, UtcOffset};
+use time::{Date, Duration, OffsetDateTime, UtcOffset};
 
 use crate::{
-    StringType, default_datetime, format_string,
+    StringType, default_datetime,
+    derived::DerivedConditions,
+    direction::CompassPoints,
+    distance::Distance,
+    format_string,
     humidity::Humidity,
+    language::Message,
     precipitation::Precipitation,
     pressure::Pressure,
+    speed::Speed,
     temperature::Temperature,
+    text_options::TextOptions,
     timestamp,
     timezone::TimeZone,
-    weather_data::{Rain, Snow, WeatherCond},
+    weather_data::{Clouds, Coord, Rain, Snow, WeatherCond, Wind},
+    weather_icon::WeatherIcon,
 };
 
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 pub struct ForecastMain {
     pub temp: Temperature,
@@ -26,6 +37,15 @@ pub struct ForecastMain {
     pub sea_level: Pressure,
     pub grnd_level: Pressure,
     pub humidity: Humidity,
+    /// Internal openweathermap.org parameter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temp_kf: Option<f64>,
+}
+
+/// Part of the day, `d` for day and `n` for night
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ForecastSys {
+    pub pod: StringType,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -38,10 +58,49 @@ pub struct ForecastEntry {
     pub rain: Option<Rain>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub snow: Option<Snow>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wind: Option<Wind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clouds: Option<Clouds>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Distance>,
+    /// Probability of precipitation, from 0.0 to 1.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pop: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sys: Option<ForecastSys>,
+    /// Forecast time in UTC, e.g. `2022-02-28 03:00:00`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dt_txt: Option<StringType>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+impl ForecastEntry {
+    /// Dew point, heat index, wind chill etc. for this forecast entry, wind
+    /// chill and apparent temperature require wind data
+    #[must_use]
+    pub fn get_derived(&self) -> DerivedConditions {
+        DerivedConditions::new(
+            self.main.temp,
+            self.main.humidity,
+            self.wind.as_ref().map(|w| w.speed),
+        )
+    }
+}
+
+/// City of a forecast, this is not `Copy` since it owns the city name and
+/// country
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct CityEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<StringType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coord: Option<Coord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<StringType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub population: Option<u64>,
     pub timezone: TimeZone,
     #[serde(with = "timestamp")]
     pub sunrise: OffsetDateTime,
@@ -52,6 +111,11 @@ pub struct CityEntry {
 impl Default for CityEntry {
     fn default() -> Self {
         Self {
+            id: None,
+            name: None,
+            coord: None,
+            country: None,
+            population: None,
             timezone: TimeZone::default(),
             sunrise: default_datetime(),
             sunset: default_datetime(),
@@ -59,14 +123,214 @@ impl Default for CityEntry {
     }
 }
 
+/// One forecast entry (usually covering 3 hours) in the local time of the
+/// forecast location
+#[derive(Debug, Clone, PartialEq)]
+pub struct HourlyForecast {
+    pub dt: OffsetDateTime,
+    pub temperature: Temperature,
+    pub feels_like: Temperature,
+    pub humidity: Humidity,
+    pub wind: Option<Wind>,
+    /// Probability of precipitation, from 0.0 to 1.0
+    pub pop: Option<f64>,
+    pub rain: Option<Precipitation>,
+    pub snow: Option<Precipitation>,
+    pub conditions: StringType,
+    pub icon: Option<WeatherIcon>,
+    pub derived: DerivedConditions,
+}
+
+/// Forecast aggregated over one local calendar day
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DailySummary {
+    pub date: Date,
+    pub high: Temperature,
+    pub low: Temperature,
+    /// Total rain
+    pub rain: Precipitation,
+    /// Total snow
+    pub snow: Precipitation,
+    pub icons: BTreeSet<StringType>,
+    /// Most frequent condition description, ties go to the earliest entry
+    pub condition: Option<StringType>,
+    pub mean_humidity: f64,
+    pub max_wind: Option<Speed>,
+    /// Maximum probability of precipitation, from 0.0 to 1.0
+    pub max_pop: Option<f64>,
+    /// Number of forecast entries aggregated
+    pub samples: usize,
+}
+
+impl DailySummary {
+    /// Aggregate `entries`, returns `None` if `entries` is empty
+    #[must_use]
+    pub fn from_entries(date: Date, entries: &[&ForecastEntry]) -> Option<Self> {
+        let mut acc = DailyAccumulator::new(date);
+        for entry in entries {
+            acc.add(entry);
+        }
+        acc.finish()
+    }
+
+    /// Most severe icon of the day
+    #[must_use]
+    pub fn get_icon(&self) -> Option<WeatherIcon> {
+        self.icons
+            .iter()
+            .filter_map(|icon| WeatherIcon::from_icon(icon))
+            .max()
+    }
+}
+
+/// Where one forecast day ends and the next begins, in local time
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum DayBoundary {
+    /// Calendar day, from midnight to midnight
+    #[default]
+    Midnight,
+    /// Meteorological day, from 6am to 6am, entries before 6am count towards
+    /// the previous date
+    #[serde(alias = "6am")]
+    #[cfg_attr(feature = "cli", value(alias = "6am"))]
+    Meteorological,
+}
+
+impl DayBoundary {
+    /// Date of the day containing local time `dt`
+    /// ```
+    /// use time::macros::{date, datetime};
+    /// use weather_util_rust::weather_forecast::DayBoundary;
+    /// let dt = datetime!(2022-02-28 04:00:00 -05:00);
+    /// assert_eq!(DayBoundary::Midnight.get_date(dt), date!(2022-02-28));
+    /// assert_eq!(DayBoundary::Meteorological.get_date(dt), date!(2022-02-27));
+    /// ```
+    #[must_use]
+    pub fn get_date(self, dt: OffsetDateTime) -> Date {
+        match self {
+            Self::Midnight => dt.date(),
+            Self::Meteorological => (dt - Duration::hours(6)).date(),
+        }
+    }
+}
+
+/// Running totals used to build a `DailySummary` one forecast entry at a time
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyAccumulator {
+    date: Date,
+    high: Option<Temperature>,
+    low: Option<Temperature>,
+    rain: Precipitation,
+    snow: Precipitation,
+    icons: BTreeSet<StringType>,
+    conditions: Vec<(StringType, usize)>,
+    humidity_sum: f64,
+    max_wind: Option<Speed>,
+    max_pop: Option<f64>,
+    samples: usize,
+}
+
+impl DailyAccumulator {
+    #[must_use]
+    pub fn new(date: Date) -> Self {
+        Self {
+            date,
+            high: None,
+            low: None,
+            rain: Precipitation::default(),
+            snow: Precipitation::default(),
+            icons: BTreeSet::new(),
+            conditions: Vec::new(),
+            humidity_sum: 0.0,
+            max_wind: None,
+            max_pop: None,
+            samples: 0,
+        }
+    }
+
+    pub fn add(&mut self, entry: &ForecastEntry) {
+        let (high, low) = (entry.main.temp_max, entry.main.temp_min);
+        if self.high.is_none_or(|h| high > h) {
+            self.high = Some(high);
+        }
+        if self.low.is_none_or(|l| low < l) {
+            self.low = Some(low);
+        }
+        if let Some(r) = entry.rain.and_then(|r| r.three_hour) {
+            self.rain = self.rain + r;
+        }
+        if let Some(s) = entry.snow.and_then(|s| s.three_hour) {
+            self.snow = self.snow + s;
+        }
+        self.icons
+            .extend(entry.weather.iter().map(|w| w.icon.clone()));
+        if let Some(w) = entry.weather.first() {
+            match self
+                .conditions
+                .iter_mut()
+                .find(|(c, _)| c == &w.description)
+            {
+                Some((_, count)) => *count += 1,
+                None => self.conditions.push((w.description.clone(), 1)),
+            }
+        }
+        self.humidity_sum += entry.main.humidity.into_inner() as f64;
+        if let Some(w) = &entry.wind
+            && self.max_wind.is_none_or(|m| w.speed.mps() > m.mps())
+        {
+            self.max_wind = Some(w.speed);
+        }
+        if let Some(pop) = entry.pop {
+            self.max_pop = Some(self.max_pop.map_or(pop, |m| m.max(pop)));
+        }
+        self.samples += 1;
+    }
+
+    /// Returns `None` if no entries were added
+    #[must_use]
+    pub fn finish(self) -> Option<DailySummary> {
+        // max_by_key returns the last maximum, so search in reverse
+        let condition = self
+            .conditions
+            .iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map(|(c, _)| c.clone());
+        Some(DailySummary {
+            date: self.date,
+            high: self.high?,
+            low: self.low?,
+            rain: self.rain,
+            snow: self.snow,
+            icons: self.icons,
+            condition,
+            mean_humidity: self.humidity_sum / self.samples as f64,
+            max_wind: self.max_wind,
+            max_pop: self.max_pop,
+            samples: self.samples,
+        })
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
 pub struct WeatherForecast {
+    /// Internal openweathermap.org parameter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cod: Option<StringType>,
+    /// Internal openweathermap.org parameter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<i64>,
+    /// Number of entries in `list`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cnt: Option<usize>,
     pub list: Vec<ForecastEntry>,
     pub city: CityEntry,
 }
 
 impl WeatherForecast {
-    /// Get Map of Date to High/Low temperatures
+    /// Get Map of Date to `DailySummary`
     /// ```
     /// # use anyhow::Error;
     /// # use std::io::{stdout, Write, Read};
@@ -85,73 +349,37 @@ impl WeatherForecast {
     ///
     /// let high_low = data.get_high_low();
     /// assert_eq!(high_low.len(), 6);
-    /// let date = date!(2022-02-27);
+    /// let summary = &high_low[&date!(2022-02-27)];
     /// let icons: BTreeSet<_> = ["04n"].iter().map(|s| (*s).into()).collect();
-    /// assert_eq!(
-    ///     high_low.get(&date),
-    ///     Some(
-    ///         &(
-    ///             Temperature::try_from(276.76)?,
-    ///             Temperature::try_from(275.01)?,
-    ///             Precipitation::default(),
-    ///             Precipitation::default(),
-    ///             icons,
-    ///         )
-    ///     )
-    /// );
+    /// assert_eq!(summary.high, Temperature::try_from(276.76)?);
+    /// assert_eq!(summary.low, Temperature::try_from(275.01)?);
+    /// assert_eq!(summary.rain, Precipitation::default());
+    /// assert_eq!(summary.icons, icons);
     /// # Ok(())
     /// # }
     /// ```
     #[must_use]
-    pub fn get_high_low(
-        &self,
-    ) -> BTreeMap<
-        Date,
-        (
-            Temperature,
-            Temperature,
-            Precipitation,
-            Precipitation,
-            BTreeSet<StringType>,
-        ),
-    > {
-        let fo: UtcOffset = self.city.timezone.into();
-        self.list.iter().fold(BTreeMap::new(), |mut hmap, entry| {
-            let date = entry.dt.to_offset(fo).date();
-            let high = entry.main.temp_max;
-            let low = entry.main.temp_min;
-            let rain = if let Some(rain) = &entry.rain {
-                rain.three_hour.unwrap_or_default()
-            } else {
-                Precipitation::default()
-            };
-            let snow = if let Some(snow) = &entry.snow {
-                snow.three_hour.unwrap_or_default()
-            } else {
-                Precipitation::default()
-            };
-            let mut icons: BTreeSet<StringType> =
-                entry.weather.iter().map(|w| w.icon.clone()).collect();
-
-            if let Some((h, l, r, s, i)) = hmap.get(&date) {
-                let high = if high > *h { high } else { *h };
-                let low = if low < *l { low } else { *l };
-                let rain = *r + rain;
-                let snow = *s + snow;
-                for ic in i {
-                    if !icons.contains(ic) {
-                        icons.insert(ic.clone());
-                    }
-                }
+    pub fn get_high_low(&self) -> BTreeMap<Date, DailySummary> {
+        self.get_daily_summaries(DayBoundary::Midnight)
+    }
 
-                if (high, low) != (*h, *l) {
-                    hmap.insert(date, (high, low, rain, snow, icons));
-                }
-            } else {
-                hmap.insert(date, (high, low, rain, snow, icons));
-            }
-            hmap
-        })
+    /// Get Map of Date to `DailySummary`, with days delimited by `boundary`
+    /// in the local time of the forecast location
+    #[must_use]
+    pub fn get_daily_summaries(&self, boundary: DayBoundary) -> BTreeMap<Date, DailySummary> {
+        let fo: UtcOffset = self.city.timezone.into();
+        let mut accumulators: BTreeMap<Date, DailyAccumulator> = BTreeMap::new();
+        for entry in &self.list {
+            let date = boundary.get_date(entry.dt.to_offset(fo));
+            accumulators
+                .entry(date)
+                .or_insert_with(|| DailyAccumulator::new(date))
+                .add(entry);
+        }
+        accumulators
+            .into_iter()
+            .filter_map(|(date, acc)| acc.finish().map(|summary| (date, summary)))
+            .collect()
     }
 
     /// Get High and Low Temperatures for the Next Few Days
@@ -160,14 +388,14 @@ impl WeatherForecast {
     /// # use std::io::{stdout, Write, Read};
     /// # use std::fs::File;
     /// # use std::convert::TryFrom;
-    /// use weather_util_rust::weather_forecast::WeatherForecast;
+    /// use weather_util_rust::{text_options::TextOptions, weather_forecast::WeatherForecast};
     /// # fn main() -> Result<(), Error> {
     /// # let mut buf = String::new();
     /// # let mut f = File::open("tests/forecast.json")?;
     /// # f.read_to_string(&mut buf)?;
     /// let data: WeatherForecast = serde_json::from_str(&buf)?;
     ///
-    /// let buf = data.get_forecast().join("");
+    /// let buf = data.get_forecast(TextOptions::default()).join("");
     ///
     /// assert!(buf.starts_with("\nForecast:"), "{}", buf);
     /// assert!(buf.contains("2022-02-27 High: 38.5 F / 3.6 C"));
@@ -176,22 +404,170 @@ impl WeatherForecast {
     /// # }
     /// ```
     #[must_use]
-    pub fn get_forecast(&self) -> Vec<StringType> {
-        let mut output = vec!["\nForecast:\n".into()];
-        output.extend(self.get_high_low().into_iter().map(|(d, (h, l, r, s, _))| {
-            let high = format_string!("High: {:0.1} F / {:0.1} C", h.fahrenheit(), h.celcius());
-            let low = format_string!("Low: {:0.1} F / {:0.1} C", l.fahrenheit(), l.celcius());
+    pub fn get_forecast(&self, options: TextOptions) -> Vec<StringType> {
+        let TextOptions {
+            units,
+            boundary,
+            icons,
+            lang,
+        } = options;
+        let mut output = vec![format_string!("\n{}:\n", lang.get(Message::Forecast))];
+        output.extend(
+            self.get_daily_summaries(boundary)
+                .into_values()
+                .map(|summary| {
+                    let icon = icons.prefix(summary.get_icon());
+                    let DailySummary {
+                        date: d,
+                        high: h,
+                        low: l,
+                        rain: r,
+                        snow: s,
+                        ..
+                    } = summary;
+                    let high = format_string!(
+                        "{}: {}",
+                        lang.get(Message::High),
+                        units.format_temperature_pair(h, 1)
+                    );
+                    let low = format_string!(
+                        "{}: {}",
+                        lang.get(Message::Low),
+                        units.format_temperature_pair(l, 1)
+                    );
+                    let mut rain_snow = String::new();
+                    if r.millimeters() > 0.0 {
+                        let rain = units.format_precipitation(r, 2);
+                        write!(rain_snow, "{} {rain}", lang.get(Message::Rain))
+                            .unwrap_or_else(|_| ());
+                    }
+                    if s.millimeters() > 0.0 {
+                        if !rain_snow.is_empty() {
+                            rain_snow.push('\t');
+                        }
+                        let snow = units.format_precipitation(s, 2);
+                        write!(rain_snow, "{} {snow}", lang.get(Message::Snow))
+                            .unwrap_or_else(|_| ());
+                    }
+                    let d = lang.format_date(d);
+                    let line = format_string!("{icon}{high:25} {low:25} {rain_snow:25}");
+                    format_string!("\t{d} {}\n", lang.localize_decimal(&line))
+                }),
+        );
+        output
+    }
+
+    /// Get forecast entries in local time which end after `now`, including
+    /// the 3 hour block already underway, limited to entries starting within
+    /// `hours` of `now`
+    /// ```
+    /// # use anyhow::Error;
+    /// # use std::io::Read;
+    /// # use std::fs::File;
+    /// use time::macros::datetime;
+    /// use weather_util_rust::weather_forecast::WeatherForecast;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut buf = String::new();
+    /// # let mut f = File::open("tests/forecast_full.json")?;
+    /// # f.read_to_string(&mut buf)?;
+    /// let data: WeatherForecast = serde_json::from_str(&buf)?;
+    ///
+    /// let hourly = data.get_hourly(Some(6), datetime!(2022-02-28 03:00 UTC));
+    /// assert_eq!(hourly.len(), 2);
+    /// assert_eq!(hourly[1].dt.hour(), 1);
+    /// assert_eq!(hourly[1].pop, Some(0.38));
+    /// assert_eq!(&hourly[1].conditions, "light rain");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn get_hourly(&self, hours: Option<u64>, now: OffsetDateTime) -> Vec<HourlyForecast> {
+        let fo: UtcOffset = self.city.timezone.into();
+        // no cutoff if now + hours overflows
+        let cutoff = hours.and_then(|h| {
+            let seconds = i64::try_from(h).ok()?.checked_mul(3600)?;
+            now.checked_add(Duration::seconds(seconds))
+        });
+        self.list
+            .iter()
+            .filter(|entry| entry.dt + Duration::hours(3) > now)
+            .take_while(|entry| cutoff.is_none_or(|cutoff| entry.dt < cutoff))
+            .map(|entry| HourlyForecast {
+                dt: entry.dt.to_offset(fo),
+                temperature: entry.main.temp,
+                feels_like: entry.main.feels_like,
+                humidity: entry.main.humidity,
+                wind: entry.wind,
+                pop: entry.pop,
+                rain: entry.rain.and_then(|r| r.three_hour),
+                snow: entry.snow.and_then(|s| s.three_hour),
+                conditions: entry
+                    .weather
+                    .first()
+                    .map_or_else(StringType::new, |w| w.description.clone()),
+                icon: entry.weather.first().and_then(WeatherCond::get_icon),
+                derived: entry.get_derived(),
+            })
+            .collect()
+    }
+
+    /// Get one line per forecast entry ending after `now`, limited to entries
+    /// starting within `hours` of `now`, each followed by the derived
+    /// quantities of the entry
+    #[must_use]
+    pub fn get_hourly_forecast(
+        &self,
+        hours: Option<u64>,
+        now: OffsetDateTime,
+        options: TextOptions,
+    ) -> Vec<StringType> {
+        let TextOptions {
+            units, icons, lang, ..
+        } = options;
+        let mut output = vec![format_string!("\n{}:\n", lang.get(Message::HourlyForecast))];
+        output.extend(self.get_hourly(hours, now).into_iter().map(|h| {
+            let dt = lang.format_datetime(h.dt);
+            let temp = units.format_temperature_pair(h.temperature, 1);
+            let feels_like = units.format_temperature_pair(h.feels_like, 1);
+            let mut wind = String::new();
+            if let Some(w) = &h.wind {
+                if let Some(deg) = w.deg {
+                    write!(wind, "{} ", deg.compass_point(CompassPoints::Sixteen))
+                        .unwrap_or_else(|_| ());
+                }
+                wind.push_str(&units.format_speed(w.speed, 0));
+            }
+            let pop = h
+                .pop
+                .map_or_else(String::new, |p| format!("{:0.0}%", p * 100.0));
             let mut rain_snow = String::new();
-            if r.millimeters() > 0.0 {
-                write!(rain_snow, "Rain {:0.2} in", r.inches()).unwrap_or_else(|_| ());
+            if let Some(r) = h.rain {
+                let rain = units.format_precipitation(r, 2);
+                write!(rain_snow, "{} {rain} ", lang.get(Message::Rain)).unwrap_or_else(|_| ());
             }
-            if s.millimeters() > 0.0 {
-                if !rain_snow.is_empty() {
-                    rain_snow.push('\t');
-                }
-                write!(rain_snow, "Snow {:0.2} in", s.inches()).unwrap_or_else(|_| ());
+            if let Some(s) = h.snow {
+                let snow = units.format_precipitation(s, 2);
+                write!(rain_snow, "{} {snow} ", lang.get(Message::Snow)).unwrap_or_else(|_| ());
             }
-            format_string!("\t{d} {high:25} {low:25} {rain_snow:25}\n")
+            let icon = icons.prefix(h.icon);
+            let line = format_string!(
+                "{temp:17} {feels_like_label} {feels_like:17} {humidity_label} {hum:>3}% \
+                 {wind_label} {wind:10} {precip_label} {pop:>4} {rain_snow}",
+                feels_like_label = lang.get(Message::FeelsLike),
+                humidity_label = lang.get(Message::Humidity),
+                hum = h.humidity,
+                wind_label = lang.get(Message::Wind),
+                precip_label = lang.get(Message::Precipitation),
+            );
+            let mut output = format_string!(
+                "\t{dt} {}{icon}{conditions}\n",
+                lang.localize_decimal(&line),
+                conditions = h.conditions,
+            );
+            for derived in h.derived.get_conditions(units, lang).lines() {
+                writeln!(output, "\t{derived}").unwrap_or(());
+            }
+            output
         }));
         output
     }
@@ -199,15 +575,353 @@ impl WeatherForecast {
 
 #[cfg(test)]
 mod test {
+    use approx::assert_abs_diff_eq;
     use log::info;
+    use proptest::prelude::*;
     use std::collections::BTreeSet;
-    use time::macros::date;
+    use time::{
+        Duration, OffsetDateTime,
+        macros::{date, datetime},
+    };
 
     use crate::{
-        Error, StringType, precipitation::Precipitation, temperature::Temperature,
-        weather_forecast::WeatherForecast,
+        Error, StringType,
+        humidity::Humidity,
+        language::Language,
+        precipitation::Precipitation,
+        pressure::Pressure,
+        speed::Speed,
+        temperature::Temperature,
+        text_options::TextOptions,
+        timezone::TimeZone,
+        unit_system::Units,
+        weather_data::{Rain, Snow, WeatherCond},
+        weather_forecast::{
+            CityEntry, DailyAccumulator, DailySummary, DayBoundary, ForecastEntry, ForecastMain,
+            WeatherForecast,
+        },
+        weather_icon::{IconStyle, WeatherIcon},
     };
 
+    const ICONS: [&str; 4] = ["01d", "02d", "10n", "13n"];
+
+    fn make_entry(
+        dt: OffsetDateTime,
+        low: f64,
+        high: f64,
+        rain: Option<f64>,
+        snow: Option<f64>,
+        icon: usize,
+    ) -> ForecastEntry {
+        let temp = Temperature::from_kelvin(low).unwrap();
+        let pressure = Pressure::from_hpa(1013.0).unwrap();
+        ForecastEntry {
+            dt,
+            main: ForecastMain {
+                temp,
+                feels_like: temp,
+                temp_min: temp,
+                temp_max: Temperature::from_kelvin(high).unwrap(),
+                pressure,
+                sea_level: pressure,
+                grnd_level: pressure,
+                humidity: Humidity::try_new(50).unwrap(),
+                temp_kf: None,
+            },
+            weather: vec![WeatherCond {
+                id: icon,
+                main: ICONS[icon].into(),
+                description: ICONS[icon].into(),
+                icon: ICONS[icon].into(),
+            }],
+            rain: rain.map(|r| Rain {
+                three_hour: Some(Precipitation::from_millimeters(r).unwrap()),
+                one_hour: None,
+            }),
+            snow: snow.map(|s| Snow {
+                three_hour: Some(Precipitation::from_millimeters(s).unwrap()),
+                one_hour: None,
+            }),
+            wind: None,
+            clouds: None,
+            visibility: None,
+            pop: None,
+            sys: None,
+            dt_txt: None,
+        }
+    }
+
+    type EntryParams = (u8, f64, f64, Option<f64>, Option<f64>, usize);
+
+    fn make_forecast(timezone: i32, params: &[EntryParams]) -> WeatherForecast {
+        let mut dt = datetime!(2022-02-27 00:00:00 UTC);
+        let list = params
+            .iter()
+            .map(|(gap, low, spread, rain, snow, icon)| {
+                dt += Duration::hours((*gap).into());
+                make_entry(dt, *low, low + spread, *rain, *snow, *icon)
+            })
+            .collect();
+        WeatherForecast {
+            list,
+            city: CityEntry {
+                timezone: TimeZone::try_new(timezone).unwrap(),
+                ..CityEntry::default()
+            },
+            ..WeatherForecast::default()
+        }
+    }
+
+    fn entry_params() -> impl Strategy<Value = EntryParams> {
+        (
+            1u8..=6,
+            250.0..300.0f64,
+            0.0..15.0f64,
+            proptest::option::of(0.0..20.0f64),
+            proptest::option::of(0.0..20.0f64),
+            0..ICONS.len(),
+        )
+    }
+
+    fn day_boundary() -> impl Strategy<Value = DayBoundary> {
+        prop_oneof![
+            Just(DayBoundary::Midnight),
+            Just(DayBoundary::Meteorological)
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn test_daily_summaries_totals(
+            timezone in -43200..=50400i32,
+            params in proptest::collection::vec(entry_params(), 0..60),
+            boundary in day_boundary(),
+        ) {
+            let forecast = make_forecast(timezone, &params);
+            let summaries = forecast.get_daily_summaries(boundary);
+
+            let samples: usize = summaries.values().map(|s| s.samples).sum();
+            prop_assert_eq!(samples, params.len());
+
+            let rain: f64 = params.iter().filter_map(|p| p.3).sum();
+            let snow: f64 = params.iter().filter_map(|p| p.4).sum();
+            let daily_rain: f64 = summaries.values().map(|s| s.rain.millimeters()).sum();
+            let daily_snow: f64 = summaries.values().map(|s| s.snow.millimeters()).sum();
+            prop_assert!((rain - daily_rain).abs() < 1e-6);
+            prop_assert!((snow - daily_snow).abs() < 1e-6);
+
+            let icons: BTreeSet<StringType> = params.iter().map(|p| ICONS[p.5].into()).collect();
+            let daily_icons: BTreeSet<StringType> =
+                summaries.values().flat_map(|s| s.icons.iter().cloned()).collect();
+            prop_assert_eq!(icons, daily_icons);
+        }
+
+        #[test]
+        fn test_daily_summaries_days(
+            timezone in -43200..=50400i32,
+            params in proptest::collection::vec(entry_params(), 1..60),
+            boundary in day_boundary(),
+        ) {
+            let forecast = make_forecast(timezone, &params);
+            let summaries = forecast.get_daily_summaries(boundary);
+            let offset = forecast.city.timezone.into();
+
+            for (date, summary) in &summaries {
+                prop_assert_eq!(*date, summary.date);
+                let entries: Vec<_> = forecast
+                    .list
+                    .iter()
+                    .filter(|e| boundary.get_date(e.dt.to_offset(offset)) == *date)
+                    .collect();
+                prop_assert_eq!(entries.len(), summary.samples);
+                let high = entries
+                    .iter()
+                    .map(|e| e.main.temp_max.kelvin())
+                    .fold(f64::MIN, f64::max);
+                let low = entries
+                    .iter()
+                    .map(|e| e.main.temp_min.kelvin())
+                    .fold(f64::MAX, f64::min);
+                prop_assert_eq!(summary.high.kelvin(), high);
+                prop_assert_eq!(summary.low.kelvin(), low);
+                prop_assert!(summary.high >= summary.low);
+            }
+        }
+    }
+
+    #[test]
+    fn test_daily_accumulator() -> Result<(), Error> {
+        let acc = DailyAccumulator::new(date!(2022 - 02 - 27));
+        assert_eq!(acc.finish(), None);
+
+        let start = datetime!(2022-02-27 00:00:00 UTC);
+        let mut acc = DailyAccumulator::new(start.date());
+        for (i, (rain, snow)) in [(Some(1.5), None), (None, None), (Some(0.5), Some(2.0))]
+            .into_iter()
+            .enumerate()
+        {
+            let dt = start + Duration::hours(3 * i as i64);
+            acc.add(&make_entry(dt, 270.0 + i as f64, 275.0, rain, snow, i));
+        }
+        let summary = acc.finish().unwrap();
+        assert_eq!(summary.samples, 3);
+        assert_abs_diff_eq!(summary.rain.millimeters(), 2.0);
+        assert_abs_diff_eq!(summary.snow.millimeters(), 2.0);
+        assert_eq!(summary.icons.len(), 3);
+        assert_eq!(summary.low, Temperature::from_kelvin(270.0)?);
+        assert_eq!(summary.mean_humidity, 50.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_day_boundary() -> Result<(), Error> {
+        let buf = include_str!("../tests/forecast_full.json");
+        let data: WeatherForecast = serde_json::from_str(buf)?;
+        // local times are 22:00, 01:00 and 04:00, all before 6am
+        let summaries = data.get_daily_summaries(DayBoundary::Meteorological);
+        assert_eq!(summaries.len(), 1);
+        let summary = &summaries[&date!(2022 - 02 - 27)];
+        assert_eq!(summary.samples, 3);
+        assert_abs_diff_eq!(summary.rain.millimeters(), 0.41);
+        assert_abs_diff_eq!(summary.snow.millimeters(), 0.87);
+
+        let buf = data
+            .get_forecast(
+                TextOptions::default()
+                    .with_units(Units::Metric.into())
+                    .with_boundary(DayBoundary::Meteorological),
+            )
+            .join("");
+        assert!(buf.contains("2022-02-27 High: 3.6 °C"));
+        assert!(!buf.contains("2022-02-28"));
+
+        let boundary: DayBoundary = serde_json::from_str(r#""6am""#)?;
+        assert_eq!(boundary, DayBoundary::Meteorological);
+        Ok(())
+    }
+
+    #[test]
+    fn test_weather_forecast_round_trip() -> Result<(), Error> {
+        let buf = include_str!("../tests/forecast_full.json");
+        let data: WeatherForecast = serde_json::from_str(buf)?;
+        assert_eq!(data.cod.as_deref(), Some(&"200".into()));
+        assert_eq!(data.cnt, Some(data.list.len()));
+        assert_eq!(data.city.name.as_deref(), Some(&"Astoria".into()));
+        assert_eq!(data.city.population, Some(78793));
+        let entry = &data.list[1];
+        assert_eq!(entry.clouds.unwrap().all, 98);
+        assert_eq!(entry.pop, Some(0.38));
+        assert_eq!(entry.sys.as_ref().unwrap().pod.as_str(), "n");
+        assert_eq!(entry.dt_txt.as_deref(), Some(&"2022-02-28 06:00:00".into()));
+        assert_eq!(entry.wind.unwrap().gust.unwrap().mps(), 8.9);
+        assert_eq!(entry.visibility.unwrap().meters(), 9000.0);
+
+        // rain and snow keep serializing as `one_hour` and `three_hour`
+        let expected: serde_json::Value =
+            serde_json::from_str(&buf.replace(r#""3h""#, r#""three_hour""#))?;
+        assert_eq!(serde_json::to_value(&data)?, expected);
+        let round_trip: WeatherForecast = serde_json::from_str(&serde_json::to_string(&data)?)?;
+        assert_eq!(round_trip, data);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_hourly() -> Result<(), Error> {
+        let buf = include_str!("../tests/forecast_full.json");
+        let data: WeatherForecast = serde_json::from_str(buf)?;
+        let now = datetime!(2022-02-28 03:00:00 UTC);
+        let hourly = data.get_hourly(None, now);
+        assert_eq!(hourly.len(), 3);
+        assert_eq!(hourly[0].dt.offset().whole_hours(), -5);
+        assert_eq!(hourly[0].dt.date(), date!(2022 - 02 - 27));
+        assert_eq!(hourly[0].rain, None);
+        assert_eq!(hourly[1].rain, Some(Precipitation::from_millimeters(0.41)?));
+        assert_eq!(hourly[2].snow, Some(Precipitation::from_millimeters(0.87)?));
+        assert_eq!(data.get_hourly(Some(1), now).len(), 1);
+        assert_eq!(data.get_hourly(Some(0), now).len(), 0);
+
+        // the block underway at now is kept, the window ends hours after now
+        let later = datetime!(2022-02-28 05:00:00 UTC);
+        let hourly = data.get_hourly(Some(3), later);
+        assert_eq!(hourly.len(), 2);
+        assert_eq!(hourly[0].dt, datetime!(2022-02-28 03:00:00 UTC));
+        assert_eq!(hourly[1].dt, datetime!(2022-02-28 06:00:00 UTC));
+        assert_eq!(data.get_hourly(None, later).len(), 3);
+        assert_eq!(data.get_hourly(Some(1), later).len(), 1);
+        assert_eq!(data.get_hourly(Some(u64::MAX), later).len(), 3);
+
+        // the block ending at now is dropped
+        let hourly = data.get_hourly(None, datetime!(2022-02-28 06:00:00 UTC));
+        assert_eq!(hourly.len(), 2);
+        assert_eq!(hourly[0].dt, datetime!(2022-02-28 06:00:00 UTC));
+
+        let output = data.get_hourly_forecast(
+            Some(9),
+            now,
+            TextOptions::default().with_units(Units::Metric.into()),
+        );
+        assert_eq!(output.len(), 4);
+        assert_eq!(output[0], "\nHourly Forecast:\n");
+        assert!(output[1].starts_with("\t2022-02-27 22:00 3.6 °C "));
+        assert!(output[1].contains("Humidity  41% Wind WNW 24 km/h"));
+        assert!(output[2].contains("Precip  38% Rain 0.4 mm light rain\n"));
+        assert!(output[3].contains("Snow 0.9 mm light snow\n"));
+
+        let output = data.get_hourly_forecast(
+            Some(9),
+            now,
+            TextOptions::default()
+                .with_units(Units::Metric.into())
+                .with_lang(Language::Fr),
+        );
+        assert_eq!(output[0], "\nPrévisions horaires:\n");
+        assert!(output[1].starts_with("\t27/02/2022 22:00 3,6 °C "));
+        assert!(output[2].contains("Précip.  38% Pluie 0,4 mm light rain\n"));
+
+        let buf = include_str!("../tests/forecast.json");
+        let data: WeatherForecast = serde_json::from_str(buf)?;
+        let output = data.get_hourly_forecast(Some(3), now, TextOptions::default());
+        assert_eq!(output.len(), 2);
+        assert!(output[1].contains("broken clouds"));
+        assert_eq!(
+            data.get_hourly(Some(3), now)[0].icon,
+            Some(WeatherIcon::BrokenClouds)
+        );
+        let output = data.get_hourly_forecast(
+            Some(3),
+            now,
+            TextOptions::default().with_icons(IconStyle::Ascii),
+        );
+        assert!(output[1].contains(" ~~~ broken clouds\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_forecast_entry_derived() -> Result<(), Error> {
+        let buf = include_str!("../tests/forecast.json");
+        let data: WeatherForecast = serde_json::from_str(buf)?;
+        let entry = &data.list[0];
+        assert_eq!(entry.wind, None);
+        let derived = entry.get_derived();
+        assert!(derived.dew_point.is_some());
+        assert!(derived.absolute_humidity.is_some());
+        assert_eq!(derived.wind_chill, None);
+        assert_eq!(derived.apparent_temperature, None);
+
+        let now = datetime!(2022-02-28 03:00:00 UTC);
+        let hourly = data.get_hourly(Some(3), now);
+        assert_eq!(hourly[0].derived, derived);
+        let output = data.get_hourly_forecast(
+            Some(3),
+            now,
+            TextOptions::default().with_units(Units::Metric.into()),
+        );
+        assert!(output[1].contains("broken clouds\n\t\tDew Point: "));
+        assert!(output[1].contains("\t\tAbsolute Humidity: "));
+        assert!(!output[1].contains("Wind Chill"));
+        Ok(())
+    }
+
     #[test]
     fn test_get_high_low() -> Result<(), Error> {
         let buf = include_str!("../tests/forecast.json");
@@ -216,17 +930,39 @@ mod test {
         assert_eq!(high_low.len(), 6);
         let date = date!(2022 - 02 - 27);
         let icons: BTreeSet<StringType> = ["04n"].iter().map(|s| (*s).into()).collect();
-        assert_eq!(
-            high_low.get(&date),
-            Some(&(
-                Temperature::from_kelvin(276.76)?,
-                Temperature::from_kelvin(275.01)?,
-                Precipitation::default(),
-                Precipitation::default(),
-                icons,
-            ))
-        );
+        let summary = &high_low[&date];
+        assert_eq!(summary.date, date);
+        assert_eq!(summary.high, Temperature::from_kelvin(276.76)?);
+        assert_eq!(summary.low, Temperature::from_kelvin(275.01)?);
+        assert_eq!(summary.rain, Precipitation::default());
+        assert_eq!(summary.snow, Precipitation::default());
+        assert_eq!(summary.icons, icons);
+        assert_eq!(summary.condition.as_deref(), Some(&"broken clouds".into()));
+        assert_eq!(summary.max_wind, None);
+        assert_eq!(summary.max_pop, None);
 
+        let buf = include_str!("../tests/forecast_full.json");
+        let data: WeatherForecast = serde_json::from_str(buf)?;
+        let high_low = data.get_high_low();
+        assert_eq!(high_low.len(), 2);
+        let summary = &high_low[&date!(2022 - 02 - 28)];
+        assert_eq!(summary.samples, 2);
+        assert_eq!(summary.high, Temperature::from_kelvin(275.4)?);
+        assert_eq!(summary.low, Temperature::from_kelvin(272.31)?);
+        assert_eq!(summary.rain, Precipitation::from_millimeters(0.41)?);
+        assert_eq!(summary.snow, Precipitation::from_millimeters(0.87)?);
+        assert_eq!(summary.icons.len(), 2);
+        assert_eq!(summary.get_icon(), Some(WeatherIcon::Snow));
+        // "light rain" and "light snow" occur once each, the earlier one wins
+        assert_eq!(summary.condition.as_deref(), Some(&"light rain".into()));
+        assert_eq!(summary.mean_humidity, 69.0);
+        assert_eq!(summary.max_wind.map(Speed::mps), Some(4.4));
+        assert_eq!(summary.max_pop, Some(0.62));
+
+        let value = serde_json::to_value(summary)?;
+        assert_eq!(value["samples"], 2);
+        let round_trip: DailySummary = serde_json::from_value(value)?;
+        assert_eq!(&round_trip, summary);
         Ok(())
     }
 
@@ -234,12 +970,38 @@ mod test {
     fn test_get_forecast() -> Result<(), Error> {
         let buf = include_str!("../tests/forecast.json");
         let data: WeatherForecast = serde_json::from_str(&buf)?;
-        let forecasts = data.get_forecast();
+        let forecasts = data.get_forecast(TextOptions::default());
         let buf = forecasts.join("");
         info!("{}", buf);
         assert!(buf.starts_with("\nForecast:"));
         assert!(buf.contains("2022-02-27 High: 38.5 F / 3.6 C"));
         assert!(buf.contains("Low: 35.3 F / 1.9 C"));
+
+        let buf = data
+            .get_forecast(TextOptions::default().with_units(Units::Metric.into()))
+            .join("");
+        assert!(buf.contains("2022-02-27 High: 3.6 °C"));
+        assert!(buf.contains("Low: 1.9 °C"));
+
+        let buf = data
+            .get_forecast(
+                TextOptions::default()
+                    .with_units(Units::Metric.into())
+                    .with_icons(IconStyle::Emoji),
+            )
+            .join("");
+        assert!(buf.contains("2022-02-27 ☁️ High: 3.6 °C"));
+
+        let buf = data
+            .get_forecast(
+                TextOptions::default()
+                    .with_units(Units::Metric.into())
+                    .with_lang(Language::De),
+            )
+            .join("");
+        assert!(buf.starts_with("\nVorhersage:\n"));
+        assert!(buf.contains("27.02.2022 Max: 3,6 °C"));
+        assert!(buf.contains("Min: 1,9 °C"));
         for f in forecasts {
             info!("{}", f.len());
         }
@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{StringType, format_string};
+
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+
+/// How weather icons are rendered in text output
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum IconStyle {
+    /// Unicode emoji
+    Emoji,
+    /// Glyphs from the weather icon range of Nerd Fonts
+    Nerd,
+    /// Three character ascii art
+    Ascii,
+    /// No icons
+    #[default]
+    None,
+}
+
+impl IconStyle {
+    /// `icon` rendered in this style followed by a space, empty if there is no
+    /// icon to render
+    #[must_use]
+    pub fn prefix(self, icon: Option<WeatherIcon>) -> StringType {
+        match icon.map(|i| i.render(self)) {
+            Some(s) if !s.is_empty() => format_string!("{s} "),
+            _ => StringType::new(),
+        }
+    }
+}
+
+/// Weather icon, ordered from least to most severe
+/// ```
+/// use weather_util_rust::weather_icon::{IconStyle, WeatherIcon};
+/// let icon = WeatherIcon::from_condition(500, "10n");
+/// assert_eq!(icon, Some(WeatherIcon::Rain));
+/// assert_eq!(WeatherIcon::Rain.render(IconStyle::Ascii), "'''");
+/// assert_eq!(WeatherIcon::Rain.render(IconStyle::None), "");
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WeatherIcon {
+    ClearNight,
+    ClearDay,
+    FewCloudsNight,
+    FewCloudsDay,
+    ScatteredClouds,
+    BrokenClouds,
+    Mist,
+    ShowerRain,
+    Rain,
+    Sleet,
+    Snow,
+    Squall,
+    Thunderstorm,
+    Tornado,
+}
+
+impl WeatherIcon {
+    /// Icon for an openweathermap.org icon code such as `04n`
+    #[must_use]
+    pub fn from_icon(icon: &str) -> Option<Self> {
+        let night = icon.ends_with('n');
+        match icon.get(..2)? {
+            "01" if night => Some(Self::ClearNight),
+            "01" => Some(Self::ClearDay),
+            "02" if night => Some(Self::FewCloudsNight),
+            "02" => Some(Self::FewCloudsDay),
+            "03" => Some(Self::ScatteredClouds),
+            "04" => Some(Self::BrokenClouds),
+            "09" => Some(Self::ShowerRain),
+            "10" => Some(Self::Rain),
+            "11" => Some(Self::Thunderstorm),
+            "13" => Some(Self::Snow),
+            "50" => Some(Self::Mist),
+            _ => None,
+        }
+    }
+
+    /// Icon for an openweathermap.org condition `id`, the icon code is used to
+    /// distinguish day from night and as a fallback for unknown ids
+    #[must_use]
+    pub fn from_condition(id: usize, icon: &str) -> Option<Self> {
+        match id {
+            200..=299 => Some(Self::Thunderstorm),
+            300..=399 | 520..=531 => Some(Self::ShowerRain),
+            511 | 611..=616 => Some(Self::Sleet),
+            500..=599 => Some(Self::Rain),
+            600..=699 => Some(Self::Snow),
+            771 => Some(Self::Squall),
+            781 => Some(Self::Tornado),
+            700..=799 => Some(Self::Mist),
+            800 | 801 => {
+                let icon = Self::from_icon(icon);
+                let night = matches!(icon, Some(Self::ClearNight | Self::FewCloudsNight));
+                Some(match (id, night) {
+                    (800, true) => Self::ClearNight,
+                    (800, false) => Self::ClearDay,
+                    (_, true) => Self::FewCloudsNight,
+                    (_, false) => Self::FewCloudsDay,
+                })
+            }
+            802 => Some(Self::ScatteredClouds),
+            803 | 804 => Some(Self::BrokenClouds),
+            _ => Self::from_icon(icon),
+        }
+    }
+
+    #[must_use]
+    pub fn render(self, style: IconStyle) -> &'static str {
+        match style {
+            IconStyle::Emoji => self.emoji(),
+            IconStyle::Nerd => self.nerd(),
+            IconStyle::Ascii => self.ascii(),
+            IconStyle::None => "",
+        }
+    }
+
+    fn emoji(self) -> &'static str {
+        match self {
+            Self::ClearNight => "🌙",
+            Self::ClearDay => "☀️",
+            Self::FewCloudsNight | Self::ScatteredClouds => "⛅",
+            Self::FewCloudsDay => "🌤️",
+            Self::BrokenClouds => "☁️",
+            Self::Mist => "🌫️",
+            Self::ShowerRain => "🌦️",
+            Self::Rain => "🌧️",
+            Self::Sleet | Self::Snow => "🌨️",
+            Self::Squall => "💨",
+            Self::Thunderstorm => "⛈️",
+            Self::Tornado => "🌪️",
+        }
+    }
+
+    fn nerd(self) -> &'static str {
+        match self {
+            Self::ClearNight => "\u{e32b}",
+            Self::ClearDay => "\u{e30d}",
+            Self::FewCloudsNight => "\u{e37e}",
+            Self::FewCloudsDay => "\u{e302}",
+            Self::ScatteredClouds => "\u{e33d}",
+            Self::BrokenClouds => "\u{e312}",
+            Self::Mist => "\u{e313}",
+            Self::ShowerRain => "\u{e319}",
+            Self::Rain => "\u{e318}",
+            Self::Sleet => "\u{e3ad}",
+            Self::Snow => "\u{e31a}",
+            Self::Squall => "\u{e34b}",
+            Self::Thunderstorm => "\u{e31d}",
+            Self::Tornado => "\u{e351}",
+        }
+    }
+
+    fn ascii(self) -> &'static str {
+        match self {
+            Self::ClearNight => "(C)",
+            Self::ClearDay => "\\O/",
+            Self::FewCloudsNight => "(C~",
+            Self::FewCloudsDay => "\\O~",
+            Self::ScatteredClouds => "~~ ",
+            Self::BrokenClouds => "~~~",
+            Self::Mist => "===",
+            Self::ShowerRain => ",~,",
+            Self::Rain => "'''",
+            Self::Sleet => "'*'",
+            Self::Snow => "***",
+            Self::Squall => ">>>",
+            Self::Thunderstorm => "~/~",
+            Self::Tornado => ")@(",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Error,
+        weather_icon::{IconStyle, WeatherIcon},
+    };
+
+    #[test]
+    fn test_weather_icon() -> Result<(), Error> {
+        for (id, icon, expected) in [
+            (800, "01d", WeatherIcon::ClearDay),
+            (800, "01n", WeatherIcon::ClearNight),
+            (801, "02n", WeatherIcon::FewCloudsNight),
+            (804, "04n", WeatherIcon::BrokenClouds),
+            (500, "10d", WeatherIcon::Rain),
+            (521, "09d", WeatherIcon::ShowerRain),
+            (511, "13d", WeatherIcon::Sleet),
+            (601, "13n", WeatherIcon::Snow),
+            (211, "11d", WeatherIcon::Thunderstorm),
+            (741, "50d", WeatherIcon::Mist),
+            (781, "50d", WeatherIcon::Tornado),
+            (0, "03d", WeatherIcon::ScatteredClouds),
+        ] {
+            assert_eq!(WeatherIcon::from_condition(id, icon), Some(expected));
+        }
+        assert_eq!(WeatherIcon::from_condition(0, "99x"), None);
+        assert_eq!(WeatherIcon::from_icon(""), None);
+        assert!(WeatherIcon::Thunderstorm > WeatherIcon::Rain);
+
+        assert_eq!(WeatherIcon::ClearDay.render(IconStyle::Emoji), "☀️");
+        assert_eq!(WeatherIcon::Snow.render(IconStyle::Nerd), "\u{e31a}");
+        assert_eq!(WeatherIcon::Snow.render(IconStyle::Ascii), "***");
+        assert_eq!(WeatherIcon::Snow.render(IconStyle::None), "");
+        assert_eq!(&IconStyle::Ascii.prefix(Some(WeatherIcon::Mist)), "=== ");
+        assert_eq!(&IconStyle::None.prefix(Some(WeatherIcon::Mist)), "");
+        assert_eq!(&IconStyle::Emoji.prefix(None), "");
+
+        let style: IconStyle = serde_json::from_str(r#""nerd""#)?;
+        assert_eq!(style, IconStyle::Nerd);
+        assert_eq!(IconStyle::default(), IconStyle::None);
+        Ok(())
+    }
+}
@@ -1,21 +1,155 @@
-use futures::future::join;
+use futures::future::{join_all, join4};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 use crate::{Error, format_string};
 
 #[cfg(feature = "cli")]
-use clap::{CommandFactory, Parser};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 
 #[cfg(feature = "cli")]
-use tokio::io::{AsyncWriteExt, stdout};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader, stderr, stdin, stdout,
+};
 
 use crate::{
-    ApiStringType, StringType, config::Config, latitude::Latitude, longitude::Longitude,
+    ApiStringType, StringType,
+    config::Config,
+    latitude::Latitude,
+    location_profile::LocationProfile,
+    longitude::Longitude,
+    unit_system::{PrecipitationUnit, PressureUnit, SpeedUnit, TemperatureUnit, UnitSystem, Units},
     weather_api::WeatherLocation,
 };
 
 #[cfg(feature = "cli")]
-use crate::weather_api::WeatherApi;
+use crate::{
+    config::save_location,
+    retry_policy::RetryPolicy,
+    weather_api::{GeoLocation, WeatherApi},
+    weather_cache::WeatherCache,
+};
+
+#[cfg(feature = "cli")]
+use isocountry::CountryCode;
+
+#[cfg(feature = "cli")]
+use std::{fmt::Write, io::IsTerminal, path::PathBuf, process::ExitCode, time::Duration};
+
+#[cfg(feature = "cli")]
+use time::{
+    Date, OffsetDateTime,
+    macros::{format_description, time},
+};
+
+#[cfg(feature = "cli")]
+use crate::{
+    direction::CompassPoints,
+    language::{Language, Message},
+    one_call::OneCallExclude,
+    template::{Template, TemplateFields},
+    text_options::TextOptions,
+    weather_data::WeatherCond,
+    weather_forecast::DayBoundary,
+    weather_icon::IconStyle,
+    weather_report::WeatherReport,
+};
+
+/// Width of `--chart` output when stdout is not a terminal
+#[cfg(feature = "cli")]
+const DEFAULT_CHART_WIDTH: usize = 80;
+
+#[cfg(feature = "cli")]
+fn get_terminal_width() -> usize {
+    terminal_size::terminal_size().map_or(DEFAULT_CHART_WIDTH, |(width, _)| width.0.into())
+}
+
+/// Exit code used when `--alerts` finds an active weather alert
+#[cfg(feature = "cli")]
+pub const ALERTS_ACTIVE_EXIT_CODE: u8 = 2;
+
+/// Format of the output written to stdout
+#[cfg(feature = "cli")]
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Human readable text
+    #[default]
+    Text,
+    /// Single line json document, see `WeatherReport`
+    Json,
+    /// Indented json document, see `WeatherReport`
+    JsonPretty,
+}
+
+/// Subcommands, without one the flags select what is printed
+#[cfg(feature = "cli")]
+#[derive(Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum WeatherCommand {
+    /// Print current conditions
+    Current,
+    /// Print current conditions and the daily forecast, same as `--forecast`
+    Forecast,
+    /// Print current conditions and each 3-hour forecast entry, same as
+    /// `--hourly`
+    Hourly {
+        /// Limit to the next N hours
+        #[clap(long)]
+        hours: Option<u64>,
+    },
+    /// Print historical conditions for a date (YYYY-MM-DD), same as `--date`
+    History {
+        // distinct id, the global `--date` would otherwise also receive the
+        // value
+        #[clap(id = "history_date", value_name = "DATE", value_parser = parse_date)]
+        date: Date,
+    },
+    /// Look up locations by name, e.g. `"Astoria,NY,US"`
+    Geocode {
+        query: StringType,
+        #[clap(flatten)]
+        selection: GeoSelection,
+    },
+    /// Look up the names of a latitude and longitude
+    #[clap(allow_negative_numbers = true)]
+    Reverse {
+        lat: Latitude,
+        lon: Longitude,
+        #[clap(flatten)]
+        selection: GeoSelection,
+    },
+    /// Look up the location of a zipcode, in the country of `--country-code`
+    Zip {
+        zipcode: u64,
+        #[clap(flatten)]
+        selection: GeoSelection,
+    },
+    /// Inspect the configuration
+    #[clap(subcommand)]
+    Config(ConfigCommand),
+}
+
+/// Choice among the results of the `geocode`, `reverse` and `zip`
+/// subcommands
+#[cfg(feature = "cli")]
+#[derive(Args, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GeoSelection {
+    /// Only print the Nth result
+    #[clap(long)]
+    pick: Option<usize>,
+    /// Save the result as a named location in config.toml, asks which result
+    /// to save if there are several and `--pick` is not given
+    #[clap(long)]
+    save: Option<StringType>,
+}
+
+#[cfg(feature = "cli")]
+#[derive(Subcommand, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigCommand {
+    /// Print the effective configuration with the source of each value,
+    /// secrets are redacted
+    Show,
+}
 
 /// Utility to retreive and format weather data from openweathermap.org
 ///
@@ -25,46 +159,151 @@ use crate::weather_api::WeatherApi;
 #[derive(Parser, Default, Serialize, Deserialize)]
 pub struct WeatherOpts {
     /// Zipcode (optional)
-    #[clap(short, long)]
+    #[clap(short, long, global = true)]
     zipcode: Option<u64>,
     /// Country Code (optional), if not specified `us` will be assumed
-    #[clap(short, long)]
+    #[clap(short, long, global = true)]
     country_code: Option<StringType>,
     /// City Name (optional)
-    #[clap(long)]
+    #[clap(long, global = true)]
     city_name: Option<StringType>,
     /// Latitude (must also specify Longitude)
-    #[clap(long)]
+    #[clap(long, global = true)]
     lat: Option<Latitude>,
     /// Longitude (must also specify Latitude)
-    #[clap(long)]
+    #[clap(long, global = true)]
     lon: Option<Longitude>,
+    /// Name of a location from the config file, e.g. `nyc-office` for
+    /// `LOCATION_NYC_OFFICE`
+    #[clap(
+        long,
+        conflicts_with_all = ["zipcode", "city_name", "lat", "lon"],
+        global = true
+    )]
+    location: Option<StringType>,
+    /// Print a table comparing current conditions at every location from the
+    /// config file, the header is in the language of `--lang` and each row in
+    /// the language of its location
+    #[serde(default)]
+    #[clap(
+        long,
+        conflicts_with_all = ["location", "date", "format"],
+        global = true
+    )]
+    all_locations: bool,
+    /// Locations for `--all-locations` with units and language resolved
+    #[serde(skip)]
+    #[clap(skip)]
+    locations: BTreeMap<StringType, LocationProfile>,
+    /// config.toml written by `--save`
+    #[serde(skip)]
+    #[clap(skip)]
+    config_file: PathBuf,
     /// Api key (optional but either this or `API_KEY` environment variable must
     /// exist)
-    #[clap(short = 'k', long)]
+    #[clap(short = 'k', long, global = true)]
     api_key: Option<ApiStringType>,
     /// Print forecast
     #[serde(default)]
-    #[clap(short, long)]
+    #[clap(short, long, global = true)]
     forecast: bool,
+    /// Print each 3-hour forecast entry
+    #[serde(default)]
+    #[clap(long, global = true)]
+    hourly: bool,
+    /// Print a chart of forecast temperature and precipitation sized to the
+    /// terminal width
+    #[serde(default)]
+    #[clap(long, global = true)]
+    chart: bool,
+    /// Limit `--hourly` to the next N hours
+    #[clap(long, requires = "hourly")]
+    hours: Option<u64>,
+    /// Print air quality index and pollutant concentrations
+    #[serde(default)]
+    #[clap(long, global = true)]
+    air_quality: bool,
+    /// Print historical conditions at local noon and a summary for the date
+    /// (YYYY-MM-DD) instead of current conditions
+    #[clap(
+        long,
+        value_parser = parse_date,
+        conflicts_with_all = ["forecast", "hourly", "chart", "air_quality"],
+        global = true
+    )]
+    date: Option<Date>,
+    /// Print active weather alerts, exits with status 2 if any alert is active
+    #[serde(default)]
+    #[clap(long, global = true)]
+    alerts: bool,
+    /// Where forecast days begin, `midnight` or `meteorological` (6am)
+    #[clap(long, value_enum, global = true)]
+    day_boundary: Option<DayBoundary>,
+    /// Weather icons in text output, `emoji`, `nerd`, `ascii` or `none`
+    /// (default)
+    #[clap(long, value_enum, global = true)]
+    icons: Option<IconStyle>,
+    /// Language of condition descriptions and text output labels (default is
+    /// en)
+    #[clap(long, value_enum, global = true)]
+    lang: Option<Language>,
+    /// Units used in text output (default is mixed)
+    #[clap(long, value_enum, global = true)]
+    units: Option<Units>,
+    /// Temperature unit, overrides `--units`
+    #[clap(long, value_enum, global = true)]
+    temperature_unit: Option<TemperatureUnit>,
+    /// Wind speed unit, overrides `--units`
+    #[clap(long, value_enum, global = true)]
+    speed_unit: Option<SpeedUnit>,
+    /// Rain and snow unit, overrides `--units`
+    #[clap(long, value_enum, global = true)]
+    precipitation_unit: Option<PrecipitationUnit>,
+    /// Pressure unit, overrides `--units`
+    #[clap(long, value_enum, global = true)]
+    pressure_unit: Option<PressureUnit>,
+    /// One line output template such as `"{name}: {temp_c:.0}°C {condition}"`,
+    /// or the name of a template from the config file, takes precedence over
+    /// `--output`
+    #[clap(long, conflicts_with = "date", global = true)]
+    format: Option<StringType>,
+    /// Output format
+    #[serde(default)]
+    #[clap(long, value_enum, default_value_t, global = true)]
+    output: OutputFormat,
+    /// Do not read or write the response cache
+    #[serde(default)]
+    #[clap(long, global = true)]
+    no_cache: bool,
+    /// Ignore cached responses, but update the cache
+    #[serde(default)]
+    #[clap(long, global = true)]
+    refresh: bool,
+    #[serde(skip)]
+    #[clap(subcommand)]
+    command: Option<WeatherCommand>,
 }
 
 #[cfg(feature = "cli")]
 impl WeatherOpts {
-    /// Parse options from stdin, requires `Config` instance.
+    /// Parse options from stdin, requires `Config` instance, returns
+    /// `ALERTS_ACTIVE_EXIT_CODE` if `--alerts` is specified and any alert is
+    /// active.
     /// # Errors
     ///
     /// Returns error if call to retreive weather data fails or if write to
     /// stdout fails
-    pub async fn parse_opts(config: &Config) -> Result<(), Error> {
+    pub async fn parse_opts(config: &Config) -> Result<ExitCode, Error> {
         let mut opts = Self::parse();
-        opts.apply_defaults(config);
+        opts.apply_defaults(config)?;
 
+        let (output, exit_code) = opts.run_opts(config).await?;
         let mut stdout = stdout();
-        for output in opts.run_opts(config).await? {
+        for output in output {
             stdout.write_all(output.as_bytes()).await?;
         }
-        Ok(())
+        stdout.flush().await?;
+        Ok(exit_code)
     }
 
     /// # Errors
@@ -76,12 +315,59 @@ impl WeatherOpts {
             .as_deref()
             .ok_or_else(|| Error::InvalidInputError(format_string!("invalid api key")))?;
 
-        Ok(WeatherApi::new(
+        let mut api = WeatherApi::new(
             api_key,
             &config.api_endpoint,
             &config.api_path,
             &config.geo_path,
-        ))
+        )
+        .with_one_call_path(&config.one_call_path);
+        if let Some(cache) = self.get_cache(config) {
+            api = api.with_cache(cache);
+        }
+        let mut retry_policy = RetryPolicy::default();
+        if let Some(max_attempts) = config.retry_max_attempts {
+            retry_policy = retry_policy.with_max_attempts(max_attempts);
+        }
+        if let Some(base_delay_ms) = config.retry_base_delay_ms {
+            retry_policy = retry_policy.with_base_delay(Duration::from_millis(base_delay_ms));
+        }
+        if let Some(jitter) = config.retry_jitter {
+            retry_policy = retry_policy.with_jitter(jitter);
+        }
+        if let Some(retry_statuses) = &config.retry_statuses {
+            retry_policy = retry_policy.with_retryable_statuses(retry_statuses);
+        }
+        api = api.with_retry_policy(retry_policy);
+        if let Some(lang) = self.lang {
+            api = api.with_language(lang);
+        }
+        if let Some(base_url) = &config.api_base_url {
+            api.with_base_url(base_url)
+        } else {
+            Ok(api)
+        }
+    }
+
+    fn get_cache(&self, config: &Config) -> Option<WeatherCache> {
+        if self.no_cache {
+            return None;
+        }
+        let directory = config
+            .cache_dir
+            .clone()
+            .or_else(WeatherCache::default_directory)?;
+        let mut cache = WeatherCache::new(&directory).with_refresh(self.refresh);
+        if let Some(ttl) = config.cache_ttl_weather {
+            cache = cache.with_weather_ttl(Duration::from_secs(ttl));
+        }
+        if let Some(ttl) = config.cache_ttl_forecast {
+            cache = cache.with_forecast_ttl(Duration::from_secs(ttl));
+        }
+        if let Some(ttl) = config.cache_ttl_geo {
+            cache = cache.with_geo_ttl(Duration::from_secs(ttl));
+        }
+        Some(cache)
     }
 
     /// Extract options from `WeatherOpts` and apply to `WeatherApi`
@@ -112,29 +398,443 @@ impl WeatherOpts {
     /// # Errors
     ///
     /// Returns error if call to retreive weather data fails
-    async fn run_opts(&self, config: &Config) -> Result<Vec<StringType>, Error> {
+    async fn run_opts(&self, config: &Config) -> Result<(Vec<StringType>, ExitCode), Error> {
+        if let Some(WeatherCommand::Config(ConfigCommand::Show)) = self.command {
+            return Ok((vec![config.show()?], ExitCode::SUCCESS));
+        }
         let api = self.get_api(config)?;
+        self.get_output(&api, OffsetDateTime::now_utc()).await
+    }
+
+    /// # Errors
+    ///
+    /// Returns error if call to retreive weather data fails
+    async fn get_output(
+        &self,
+        api: &WeatherApi,
+        now: OffsetDateTime,
+    ) -> Result<(Vec<StringType>, ExitCode), Error> {
+        if self.all_locations {
+            let output = self.get_all_locations_output(api).await?;
+            return Ok((output, ExitCode::SUCCESS));
+        }
+        if let Some(output) = self.get_geo_output(api).await? {
+            return Ok((output, ExitCode::SUCCESS));
+        }
         let loc = self.get_location()?;
+        let template = self
+            .format
+            .as_ref()
+            .map(|format| format.parse::<Template>())
+            .transpose()?;
+        if let Some(date) = self.date {
+            // resolve the location once rather than in each of the two calls
+            let loc = loc.to_lat_lon(api).await?;
+            // the day summary is in the timezone of the location, use its
+            // offset to request conditions at local noon
+            let summary = api.get_day_summary(&loc, date).await?;
+            let dt = date
+                .with_time(time!(12:00))
+                .assume_offset(summary.get_offset());
+            let data = api.get_historical(&loc, dt).await?;
+            let output = if self.output == OutputFormat::Text {
+                let (units, lang) = (self.get_units(), self.get_lang());
+                vec![
+                    data.get_historical_conditions(units, lang),
+                    summary.get_day_summary(units, lang),
+                ]
+            } else {
+                let report = WeatherReport::from_time_machine(&data)
+                    .ok_or_else(|| {
+                        Error::InvalidValue(format_string!("no historical data for {date}"))
+                    })?
+                    .with_day_summary(&summary);
+                vec![self.format_report(&report)?]
+            };
+            return Ok((output, ExitCode::SUCCESS));
+        }
 
         let data = api.get_weather_data(&loc);
-        let (data, forecast) = if self.forecast {
-            let forecast = api.get_weather_forecast(&loc);
-            let (data, forecast) = join(data, forecast).await;
-            (data?, Some(forecast?))
-        } else {
-            (data.await?, None)
+        let forecast = async {
+            if self.forecast
+                || self.hourly
+                || self.chart
+                || template.as_ref().is_some_and(Template::uses_forecast)
+            {
+                api.get_weather_forecast(&loc).await.map(Some)
+            } else {
+                Ok(None)
+            }
+        };
+        let air_quality = async {
+            if self.air_quality {
+                api.get_air_pollution(&loc).await.map(Some)
+            } else {
+                Ok(None)
+            }
         };
-        let mut output = vec![data.get_current_conditions()];
+        let alerts = async {
+            if self.alerts {
+                let exclude = [
+                    OneCallExclude::Current,
+                    OneCallExclude::Minutely,
+                    OneCallExclude::Hourly,
+                    OneCallExclude::Daily,
+                ];
+                api.get_one_call(&loc, &exclude).await.map(Some)
+            } else {
+                Ok(None)
+            }
+        };
+        let (data, forecast, air_quality, alerts) =
+            join4(data, forecast, air_quality, alerts).await;
+        let (data, forecast, air_quality, alerts) = (data?, forecast?, air_quality?, alerts?);
+        let exit_code = match &alerts {
+            Some(alerts) if !alerts.get_active_alerts(now).is_empty() => {
+                ALERTS_ACTIVE_EXIT_CODE.into()
+            }
+            _ => ExitCode::SUCCESS,
+        };
+        if let Some(template) = &template {
+            let mut fields = TemplateFields::from_weather_data(&data, self.get_icons());
+            if let Some(forecast) = &forecast {
+                fields = fields.with_forecast(forecast, self.get_day_boundary(), self.get_icons());
+            }
+            let mut output = template.render(&fields);
+            output.push('\n');
+            return Ok((vec![output], exit_code));
+        }
+        if self.output != OutputFormat::Text {
+            let mut report = WeatherReport::from_weather_data(&data);
+            if let Some(forecast) = &forecast {
+                if self.forecast {
+                    report = report.with_forecast(forecast, self.get_day_boundary());
+                }
+                if self.hourly {
+                    report = report.with_hourly(forecast, self.hours, now);
+                }
+            }
+            if let Some(air_quality) = &air_quality {
+                report = report.with_air_quality(air_quality);
+            }
+            if let Some(alerts) = &alerts {
+                report = report.with_alerts(&alerts.get_active_alerts(now));
+            }
+            return Ok((vec![self.format_report(&report)?], exit_code));
+        }
+        let options = self.get_text_options();
+        let (units, lang) = (options.units, options.lang);
+        let mut output = vec![data.get_current_conditions(options)];
+        if let Some(alerts) = alerts {
+            output.extend(alerts.get_alerts(now, lang));
+        }
+        if let Some(air_quality) = air_quality {
+            output.push(air_quality.get_air_quality(lang));
+        }
         if let Some(forecast) = forecast {
-            output.extend(forecast.get_forecast());
+            if self.hourly {
+                output.extend(forecast.get_hourly_forecast(self.hours, now, options));
+            }
+            if self.forecast {
+                output.extend(forecast.get_forecast(options));
+            }
+            if self.chart {
+                output.extend(forecast.get_chart(get_terminal_width(), units, lang));
+            }
         }
+        Ok((output, exit_code))
+    }
+
+    /// Output of the `geocode`, `reverse` and `zip` subcommands, one numbered
+    /// line per location followed by its local names, `None` for other
+    /// subcommands
+    /// # Errors
+    ///
+    /// Returns error if the country code is invalid, if the geocoding api
+    /// call fails, if `--pick` is out of range or if `--save` fails
+    async fn get_geo_output(&self, api: &WeatherApi) -> Result<Option<Vec<StringType>>, Error> {
+        let (locations, selection) = match &self.command {
+            Some(WeatherCommand::Geocode { query, selection }) => {
+                (api.get_direct_location(query).await?, selection)
+            }
+            Some(WeatherCommand::Reverse {
+                lat,
+                lon,
+                selection,
+            }) => (api.get_geo_location(*lat, *lon).await?, selection),
+            Some(WeatherCommand::Zip { zipcode, selection }) => {
+                let country_code = self
+                    .country_code
+                    .as_ref()
+                    .map(|c| {
+                        CountryCode::for_alpha2_caseless(c).map_err(|_| {
+                            Error::InvalidInputError(format_string!("invalid country code {c}"))
+                        })
+                    })
+                    .transpose()?;
+                (
+                    vec![api.get_zip_location(*zipcode, country_code).await?],
+                    selection,
+                )
+            }
+            _ => return Ok(None),
+        };
+        let mut candidates: Vec<_> = (1..).zip(locations).collect();
+        let pick = match selection.pick {
+            Some(pick) => Some(pick),
+            None if selection.save.is_some() && candidates.len() > 1 => {
+                let is_terminal = std::io::stdin().is_terminal();
+                Some(
+                    prompt_pick(&candidates, is_terminal, BufReader::new(stdin()), stderr())
+                        .await?,
+                )
+            }
+            None => None,
+        };
+        if let Some(pick) = pick {
+            if pick == 0 || pick > candidates.len() {
+                return Err(Error::InvalidValue(format_string!(
+                    "--pick {pick} is out of range, found {} locations",
+                    candidates.len()
+                )));
+            }
+            candidates = vec![candidates.swap_remove(pick - 1)];
+        }
+        let mut output: Vec<StringType> = if self.output == OutputFormat::Text {
+            candidates
+                .iter()
+                .map(|(i, location)| format_geo_location(*i, location, self.get_lang()))
+                .collect()
+        } else {
+            let locations: Vec<_> = candidates.iter().map(|(_, location)| location).collect();
+            vec![self.format_report(&locations)?]
+        };
+        if let Some(name) = &selection.save
+            && let Some((_, location)) = candidates.first()
+        {
+            if self.config_file.as_os_str().is_empty() {
+                return Err(Error::InvalidValue(format_string!(
+                    "no config file to save {name} to"
+                )));
+            }
+            let (lat, lon) = location.get_lat_lon()?;
+            let profile = LocationProfile {
+                lat: Some(lat),
+                lon: Some(lon),
+                ..LocationProfile::default()
+            };
+            save_location(&self.config_file, name, &profile, &location.to_string())?;
+            if self.output == OutputFormat::Text {
+                output.push(format_string!(
+                    "Saved {name} to {}\n",
+                    self.config_file.display()
+                ));
+            }
+        }
+        Ok(Some(output))
+    }
+
+    /// Fetch current conditions at all named locations concurrently and
+    /// format them as a table, or as a json object keyed by location name,
+    /// a location for which the call fails shows the error in its row
+    /// # Errors
+    ///
+    /// Returns error if there are no named locations
+    async fn get_all_locations_output(&self, api: &WeatherApi) -> Result<Vec<StringType>, Error> {
+        if self.locations.is_empty() {
+            return Err(Error::InvalidValue(format_string!(
+                "no named locations, add LOCATION_<NAME> to the config file"
+            )));
+        }
+        let results = join_all(self.locations.iter().map(|(name, profile)| async move {
+            let data = async {
+                let loc = profile.get_location()?;
+                if let Some(lang) = profile.lang {
+                    api.clone().with_language(lang).get_weather_data(&loc).await
+                } else {
+                    api.get_weather_data(&loc).await
+                }
+            };
+            (name, profile, data.await)
+        }))
+        .await;
+
+        if self.output != OutputFormat::Text {
+            let reports: BTreeMap<_, _> = results
+                .iter()
+                .map(|(name, _, data)| {
+                    let report = match data {
+                        Ok(data) => {
+                            LocationReport::Report(Box::new(WeatherReport::from_weather_data(data)))
+                        }
+                        Err(e) => LocationReport::Error {
+                            error: format_string!("{e}"),
+                        },
+                    };
+                    (*name, report)
+                })
+                .collect();
+            return Ok(vec![self.format_report(&reports)?]);
+        }
+        let lang = self.get_lang();
+        let icons = self.get_icons();
+        let header = [
+            Message::Location,
+            Message::Temperature,
+            Message::Humidity,
+            Message::Wind,
+            Message::Conditions,
+        ]
+        .map(|m| StringType::from(lang.get(m)));
+        let mut rows = vec![header];
+        for (name, profile, data) in &results {
+            let data = match data {
+                Ok(data) => data,
+                Err(e) => {
+                    rows.push([
+                        (*name).clone(),
+                        StringType::new(),
+                        StringType::new(),
+                        StringType::new(),
+                        format_string!("error: {e}"),
+                    ]);
+                    continue;
+                }
+            };
+            let units = self.get_units_for(profile.units);
+            let lang = profile.lang.unwrap_or_default();
+            let mut wind = StringType::new();
+            if let Some(direction) = data.wind.deg {
+                wind.push_str(direction.compass_point(CompassPoints::Sixteen));
+                wind.push(' ');
+            }
+            wind.push_str(&units.format_speed(data.wind.speed, 0));
+            let cond = data.weather.first();
+            rows.push([
+                (*name).clone(),
+                lang.localize_decimal(&units.format_temperature(data.main.temp, 1)),
+                format_string!("{}%", data.main.humidity),
+                wind,
+                format_string!(
+                    "{}{}",
+                    icons.prefix(cond.and_then(WeatherCond::get_icon)),
+                    cond.map_or("", |w| &w.description)
+                ),
+            ]);
+        }
+        Ok(vec![format_table(&rows)])
+    }
+
+    fn format_report(&self, report: &impl Serialize) -> Result<StringType, Error> {
+        let mut output: StringType = if self.output == OutputFormat::JsonPretty {
+            serde_json::to_string_pretty(report)?
+        } else {
+            serde_json::to_string(report)?
+        }
+        .into();
+        output.push('\n');
         Ok(output)
     }
 
-    fn apply_defaults(&mut self, config: &Config) {
+    /// Set the flags equivalent to the `current`, `forecast`, `hourly` and
+    /// `history` subcommands
+    /// # Errors
+    ///
+    /// Returns error if the subcommand is combined with a flag it overrides or
+    /// ignores, e.g. `history` with `--format`
+    fn apply_command(&mut self) -> Result<(), Error> {
+        let (name, conflicts) = match self.command {
+            Some(WeatherCommand::Current) => (
+                "current",
+                vec![
+                    (self.date.is_some(), "--date"),
+                    (self.forecast, "--forecast"),
+                    (self.hourly, "--hourly"),
+                    (self.chart, "--chart"),
+                    (self.all_locations, "--all-locations"),
+                ],
+            ),
+            Some(WeatherCommand::Forecast) => ("forecast", vec![(self.date.is_some(), "--date")]),
+            Some(WeatherCommand::Hourly { .. }) => {
+                ("hourly", vec![(self.date.is_some(), "--date")])
+            }
+            Some(WeatherCommand::History { .. }) => (
+                "history",
+                vec![
+                    (self.date.is_some(), "--date"),
+                    (self.forecast, "--forecast"),
+                    (self.hourly, "--hourly"),
+                    (self.chart, "--chart"),
+                    (self.air_quality, "--air-quality"),
+                    (self.format.is_some(), "--format"),
+                    (self.all_locations, "--all-locations"),
+                ],
+            ),
+            _ => ("", Vec::new()),
+        };
+        if let Some((_, flag)) = conflicts.into_iter().find(|(set, _)| *set) {
+            return Err(Error::InvalidValue(format_string!(
+                "{flag} cannot be used with the {name} subcommand"
+            )));
+        }
+        match self.command {
+            Some(WeatherCommand::Forecast) => self.forecast = true,
+            Some(WeatherCommand::Hourly { hours }) => {
+                self.hourly = true;
+                if hours.is_some() {
+                    self.hours = hours;
+                }
+            }
+            Some(WeatherCommand::History { date }) => self.date = Some(date),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// Returns error if `--location` is not a named location from the config
+    /// or if the subcommand conflicts with one of the flags
+    fn apply_defaults(&mut self, config: &Config) -> Result<(), Error> {
+        self.apply_command()?;
+        self.config_file.clone_from(&config.toml_file);
         if self.api_key.is_none() {
             self.api_key.clone_from(&config.api_key);
         }
+        if let Some(name) = &self.location {
+            let profile = config.locations.get(name).ok_or_else(|| {
+                let names: Vec<_> = config.locations.keys().map(StringType::as_str).collect();
+                Error::InvalidValue(format_string!(
+                    "unknown location {name}, expected one of [{}]",
+                    names.join(", ")
+                ))
+            })?;
+            self.zipcode = profile.zipcode;
+            self.country_code.clone_from(&profile.country_code);
+            self.city_name.clone_from(&profile.city_name);
+            self.lat = profile.lat;
+            self.lon = profile.lon;
+            if self.units.is_none() {
+                self.units = profile.units;
+            }
+            if self.lang.is_none() {
+                self.lang = profile.lang;
+            }
+        }
+        if self.all_locations {
+            // command line options take precedence over the per-location
+            // overrides, which take precedence over the config defaults
+            self.locations = config
+                .locations
+                .iter()
+                .map(|(name, profile)| {
+                    let mut profile = profile.clone();
+                    profile.units = self.units.or(profile.units).or(config.units);
+                    profile.lang = self.lang.or(profile.lang).or(config.weather_lang);
+                    (name.clone(), profile)
+                })
+                .collect();
+        }
         if self.zipcode.is_none()
             && self.country_code.is_none()
             && self.city_name.is_none()
@@ -148,6 +848,96 @@ impl WeatherOpts {
                 self.lon = config.lon;
             }
         }
+        if self.units.is_none() {
+            self.units = config.units;
+        }
+        if self.day_boundary.is_none() {
+            self.day_boundary = config.day_boundary;
+        }
+        if self.icons.is_none() {
+            self.icons = config.icons;
+        }
+        if self.lang.is_none() {
+            self.lang = config.weather_lang;
+        }
+        if let Some(format) = &self.format {
+            if let Some(template) = config.templates.get(format) {
+                self.format = Some(template.clone());
+            } else if !format.contains('{') {
+                return Err(Error::InvalidValue(format_string!(
+                    "unknown template {format}"
+                )));
+            }
+        }
+        if self.temperature_unit.is_none() {
+            self.temperature_unit = config.temperature_unit;
+        }
+        if self.speed_unit.is_none() {
+            self.speed_unit = config.speed_unit;
+        }
+        if self.precipitation_unit.is_none() {
+            self.precipitation_unit = config.precipitation_unit;
+        }
+        if self.pressure_unit.is_none() {
+            self.pressure_unit = config.pressure_unit;
+        }
+        Ok(())
+    }
+
+    /// `UnitSystem` selected by `--units` with the per-quantity overrides
+    /// applied
+    #[must_use]
+    pub fn get_units(&self) -> UnitSystem {
+        self.get_units_for(self.units)
+    }
+
+    /// `UnitSystem` for `units`, e.g. the units of a named location, with the
+    /// per-quantity overrides applied
+    #[must_use]
+    pub fn get_units_for(&self, units: Option<Units>) -> UnitSystem {
+        let mut units: UnitSystem = units.unwrap_or_default().into();
+        if let Some(temperature) = self.temperature_unit {
+            units = units.with_temperature(temperature);
+        }
+        if let Some(speed) = self.speed_unit {
+            units = units.with_speed(speed);
+        }
+        if let Some(precipitation) = self.precipitation_unit {
+            units = units.with_precipitation(precipitation);
+        }
+        if let Some(pressure) = self.pressure_unit {
+            units = units.with_pressure(pressure);
+        }
+        units
+    }
+
+    /// `DayBoundary` selected by `--day-boundary`, midnight by default
+    #[must_use]
+    pub fn get_day_boundary(&self) -> DayBoundary {
+        self.day_boundary.unwrap_or_default()
+    }
+
+    /// `Language` selected by `--lang`, English by default
+    #[must_use]
+    pub fn get_lang(&self) -> Language {
+        self.lang.unwrap_or_default()
+    }
+
+    /// `IconStyle` selected by `--icons`, no icons by default
+    #[must_use]
+    pub fn get_icons(&self) -> IconStyle {
+        self.icons.unwrap_or_default()
+    }
+
+    /// Units, day boundary, icons and language of text output
+    #[must_use]
+    pub fn get_text_options(&self) -> TextOptions {
+        TextOptions {
+            units: self.get_units(),
+            boundary: self.get_day_boundary(),
+            icons: self.get_icons(),
+            lang: self.get_lang(),
+        }
     }
 
     #[must_use]
@@ -156,6 +946,102 @@ impl WeatherOpts {
     }
 }
 
+/// Numbered line for a geocoding result, followed by an indented line with its
+/// local names in `lang` and English, json output has all of them
+#[cfg(feature = "cli")]
+fn format_geo_location(index: usize, location: &GeoLocation, lang: Language) -> StringType {
+    let mut output = format_string!("{index}. {location}\n");
+    let mut codes = vec![lang.api_code()];
+    if lang != Language::En {
+        codes.push(Language::En.api_code());
+    }
+    let local_names: Vec<_> = codes
+        .into_iter()
+        .filter_map(|code| {
+            let name = location.local_names.as_ref()?.get(code)?;
+            Some(format_string!("{code}: {name}"))
+        })
+        .collect();
+    if !local_names.is_empty() {
+        writeln!(output, "   {}", local_names.join(", ")).unwrap_or(());
+    }
+    output
+}
+
+/// Ask which of the numbered `candidates` to use, the prompt is written to
+/// `writer` and the answer read from `reader`
+/// # Errors
+///
+/// Returns error if the input is not a terminal, if reading or writing fails
+/// or if the answer is not a number
+#[cfg(feature = "cli")]
+async fn prompt_pick(
+    candidates: &[(usize, GeoLocation)],
+    is_terminal: bool,
+    mut reader: impl AsyncBufRead + Unpin,
+    mut writer: impl AsyncWrite + Unpin,
+) -> Result<usize, Error> {
+    if !is_terminal {
+        return Err(Error::InvalidValue(format_string!(
+            "found {} locations, use --pick N to choose one",
+            candidates.len()
+        )));
+    }
+    let mut prompt = StringType::new();
+    for (index, location) in candidates {
+        writeln!(prompt, "{index}. {location}")?;
+    }
+    write!(prompt, "Pick a location [1-{}]: ", candidates.len())?;
+    writer.write_all(prompt.as_bytes()).await?;
+    writer.flush().await?;
+    let mut answer = String::new();
+    reader.read_line(&mut answer).await?;
+    answer
+        .trim()
+        .parse()
+        .map_err(|_| Error::InvalidValue(format_string!("invalid choice {}", answer.trim())))
+}
+
+/// Entry of the json output of `--all-locations`
+#[cfg(feature = "cli")]
+#[derive(Serialize)]
+#[serde(untagged)]
+enum LocationReport {
+    Report(Box<WeatherReport>),
+    Error { error: StringType },
+}
+
+/// Align the columns of `rows`, the first row is the header
+#[cfg(feature = "cli")]
+fn format_table<const N: usize>(rows: &[[StringType; N]]) -> StringType {
+    let mut widths = [0; N];
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut output = StringType::new();
+    for row in rows {
+        let mut line = StringType::new();
+        for (width, cell) in widths.iter().zip(row) {
+            write!(line, "{cell:width$}  ").unwrap_or(());
+        }
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+/// Parse a date in the format YYYY-MM-DD
+/// # Errors
+///
+/// Returns error if `s` is not a valid date
+#[cfg(feature = "cli")]
+pub fn parse_date(s: &str) -> Result<Date, Error> {
+    Date::parse(s, format_description!("[year]-[month]-[day]"))
+        .map_err(|e| Error::InvalidValue(format_string!("invalid date {s}: {e}")))
+}
+
 #[cfg(test)]
 mod test {
     use isocountry::CountryCode;
@@ -163,7 +1049,7 @@ mod test {
     use std::{convert::TryFrom, env::set_var};
 
     use crate::{
-        Error,
+        Error, StringType,
         config::{Config, TestEnvs},
         latitude::Latitude,
         longitude::Longitude,
@@ -171,7 +1057,31 @@ mod test {
     };
 
     #[cfg(feature = "cli")]
-    use crate::weather_opts::WeatherOpts;
+    use crate::{
+        language::Language,
+        unit_system::{PressureUnit, SpeedUnit, TemperatureUnit, UnitSystem, Units},
+        weather_api::{GeoLocation, WeatherApi},
+        weather_cache::CacheCategory,
+        weather_icon::IconStyle,
+        weather_opts::{
+            ALERTS_ACTIVE_EXIT_CODE, ConfigCommand, OutputFormat, WeatherCommand, WeatherOpts,
+            parse_date, prompt_pick,
+        },
+        weather_report::WeatherReport,
+        weather_transport::{FixtureTransport, TransportResponse},
+    };
+
+    #[cfg(feature = "cli")]
+    use std::{collections::BTreeMap, path::Path, process::ExitCode, time::Duration};
+
+    #[cfg(feature = "cli")]
+    use tempfile::TempDir;
+
+    #[cfg(feature = "cli")]
+    use clap::{Parser, ValueEnum};
+
+    #[cfg(feature = "cli")]
+    use time::macros::datetime;
 
     #[cfg(feature = "cli")]
     #[test]
@@ -189,7 +1099,7 @@ mod test {
         drop(_env);
 
         let mut opts = WeatherOpts::default();
-        opts.apply_defaults(&config);
+        opts.apply_defaults(&config)?;
         let api = opts.get_api(&config)?;
         assert_eq!(
             format!("{api:?}"),
@@ -204,6 +1114,70 @@ mod test {
         Ok(())
     }
 
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_get_api_base_url() -> Result<(), Error> {
+        let _env = TestEnvs::new(&["API_KEY", "API_ENDPOINT", "API_BASE_URL"]);
+
+        unsafe {
+            set_var("API_KEY", "1234567");
+            set_var("API_ENDPOINT", "test.local1");
+            set_var("API_BASE_URL", "http://localhost:8080/owm/");
+        }
+
+        let config = Config::init_config(None)?;
+
+        unsafe {
+            set_var("API_BASE_URL", "localhost:8080");
+        }
+
+        let bad_config = Config::init_config(None)?;
+        drop(_env);
+
+        let mut opts = WeatherOpts::default();
+        opts.apply_defaults(&config)?;
+        let api = opts.get_api(&config)?;
+        assert_eq!(
+            format!("{api:?}"),
+            "WeatherApi(key=1234567,endpoint=http://localhost:8080/owm/)".to_string()
+        );
+        assert!(opts.get_api(&bad_config).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_get_cache() -> Result<(), Error> {
+        let _env = TestEnvs::new(&["CACHE_DIR", "CACHE_TTL_WEATHER"]);
+
+        unsafe {
+            set_var("CACHE_DIR", "/tmp/weather_util_cache");
+            set_var("CACHE_TTL_WEATHER", "60");
+        }
+
+        let config = Config::init_config(None)?;
+        drop(_env);
+
+        let opts = WeatherOpts::default();
+        let cache = opts.get_cache(&config).unwrap();
+        assert_eq!(cache.directory(), Path::new("/tmp/weather_util_cache"));
+        assert_eq!(
+            cache.get_ttl(CacheCategory::Weather),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            cache.get_ttl(CacheCategory::Forecast),
+            Duration::from_secs(3600)
+        );
+
+        let opts = WeatherOpts {
+            no_cache: true,
+            ..WeatherOpts::default()
+        };
+        assert!(opts.get_cache(&config).is_none());
+        Ok(())
+    }
+
     #[test]
     fn test_apply_defaults() -> Result<(), Error> {
         let _env = TestEnvs::new(&["API_KEY", "API_ENDPOINT", "LAT", "LON", "API_PATH"]);
@@ -220,13 +1194,229 @@ mod test {
         drop(_env);
 
         let mut opts = WeatherOpts::default();
-        opts.apply_defaults(&config);
+        opts.apply_defaults(&config)?;
 
         assert_eq!(opts.lat, Some(Latitude::try_from(10.1)?));
         assert_eq!(opts.lon, Some(Longitude::try_from(11.1)?));
         Ok(())
     }
 
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_subcommands() -> Result<(), Error> {
+        let config = Config::default();
+        let parse = |args: &[&str]| -> Result<WeatherOpts, Error> {
+            let mut opts =
+                WeatherOpts::try_parse_from(["weather-util-rust"].iter().chain(args.iter()))?;
+            opts.apply_defaults(&config)?;
+            Ok(opts)
+        };
+
+        let opts = parse(&["-z", "11106", "-f"])?;
+        assert_eq!(opts.command, None);
+        assert!(opts.forecast);
+        let opts = parse(&["forecast", "-z", "11106"])?;
+        assert!(opts.forecast);
+        assert_eq!(opts.zipcode, Some(11106));
+        let opts = parse(&["--units", "metric", "current"])?;
+        assert!(!opts.forecast);
+        assert_eq!(opts.units, Some(Units::Metric));
+        let opts = parse(&["hourly", "--hours", "6", "--lat", "40.76", "--lon=-73.93"])?;
+        assert!(opts.hourly);
+        assert_eq!(opts.hours, Some(6));
+        let opts = parse(&["history", "2024-03-01", "-z", "11106"])?;
+        assert_eq!(opts.date, Some(parse_date("2024-03-01")?));
+        assert!(parse(&["history", "yesterday"]).is_err());
+
+        for args in [
+            &["history", "2024-03-01", "--format", "{temp_c}"][..],
+            &["history", "2024-03-01", "--all-locations"],
+            &["history", "2024-03-01", "--date", "2024-03-02"],
+            &["history", "2024-03-01", "-f"],
+            &["history", "2024-03-01", "--hourly"],
+            &["history", "2024-03-01", "--chart"],
+            &["history", "2024-03-01", "--air-quality"],
+            &["-f", "current"],
+            &["current", "--hourly"],
+            &["current", "--date", "2024-03-01"],
+            &["current", "--chart"],
+            &["current", "--all-locations"],
+            &["forecast", "--date", "2024-03-01"],
+            &["hourly", "--date", "2024-03-01"],
+        ] {
+            let Err(Error::InvalidValue(e)) = parse(args) else {
+                panic!("expected {args:?} to be rejected");
+            };
+            assert!(e.contains("cannot be used with the"), "{e}");
+        }
+        let opts = parse(&["current", "--air-quality", "--alerts"])?;
+        assert!(opts.air_quality && opts.alerts);
+
+        let transport = FixtureTransport::new()
+            .with_fixture(
+                "geo/1.0/zip",
+                &[("zip", "11106,US")],
+                include_str!("../tests/geo_zip.json"),
+            )
+            .with_fixture(
+                "geo/1.0/direct",
+                &[("q", "Astoria,NY,US")],
+                include_str!("../tests/geo_direct.json"),
+            )
+            .with_fixture(
+                "geo/1.0/reverse",
+                &[("lat", "40.76000"), ("lon", "-73.93000")],
+                include_str!("../tests/geo_reverse.json"),
+            );
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport);
+        let now = datetime!(2022-02-28 03:00:00 UTC);
+
+        let (output, _) = parse(&["geocode", "Astoria,NY,US"])?
+            .get_output(&api, now)
+            .await?;
+        assert_eq!(
+            output,
+            vec![
+                "1. Astoria, New York, US 40.77201N -73.93026E\n   en: Astoria\n",
+                "2. Astoria, Oregon, US 46.18788N -123.83125E\n"
+            ]
+        );
+        let (output, _) = parse(&["geocode", "Astoria,NY,US", "--output", "json"])?
+            .get_output(&api, now)
+            .await?;
+        let locations: Vec<GeoLocation> = serde_json::from_str(&output[0])?;
+        let local_names = locations[0].local_names.as_ref().unwrap();
+        assert_eq!(
+            local_names.get("ru").map(StringType::as_str),
+            Some("Астория")
+        );
+        let (output, _) = parse(&["geocode", "Astoria,NY,US", "--pick", "2"])?
+            .get_output(&api, now)
+            .await?;
+        assert_eq!(
+            output,
+            vec!["2. Astoria, Oregon, US 46.18788N -123.83125E\n"]
+        );
+        assert!(matches!(
+            parse(&["geocode", "Astoria,NY,US", "--pick", "3"])?
+                .get_output(&api, now)
+                .await,
+            Err(Error::InvalidValue(e)) if &e == "--pick 3 is out of range, found 2 locations"
+        ));
+        let directory = TempDir::new()?;
+        let config_file = directory.path().join("weather_util").join("config.toml");
+        let mut opts = parse(&[
+            "geocode",
+            "Astoria,NY,US",
+            "--pick",
+            "1",
+            "--save",
+            "astoria",
+        ])?;
+        opts.config_file.clone_from(&config_file);
+        let (output, _) = opts.get_output(&api, now).await?;
+        assert_eq!(output.len(), 2);
+        assert_eq!(
+            output[1],
+            format!("Saved astoria to {}\n", config_file.display())
+        );
+        let mut opts = parse(&["zip", "11106", "--save", "home"])?;
+        opts.config_file.clone_from(&config_file);
+        opts.get_output(&api, now).await?;
+        assert_eq!(
+            std::fs::read_to_string(&config_file)?,
+            "# Astoria, New York, US 40.77201N -73.93026E\n[locations.astoria]\nlat = \
+             40.77201\nlon = -73.93026\n\n# 11106 New York, US 40.76080N \
+             -73.92950E\n[locations.home]\nlat = 40.7608\nlon = -73.9295\n"
+        );
+        // already saved
+        assert!(opts.get_output(&api, now).await.is_err());
+
+        let _env = TestEnvs::new(&["API_KEY"]);
+        let config = Config::init_config(Some(&config_file))?;
+        drop(_env);
+        assert_eq!(config.toml_file, config_file);
+        let location = &config.locations["home"];
+        assert_eq!(location.get_location()?.to_string(), "40.76080,-73.92950");
+        let (output, _) = parse(&["reverse", "40.76", "-73.93"])?
+            .get_output(&api, now)
+            .await?;
+        assert_eq!(
+            output,
+            vec!["1. New York, New York, US 40.71273N -74.00602E\n   en: New York\n"]
+        );
+        let (output, _) = parse(&["reverse", "40.76", "-73.93", "--lang", "es"])?
+            .get_output(&api, now)
+            .await?;
+        assert_eq!(
+            output,
+            vec![
+                "1. New York, New York, US 40.71273N -74.00602E\n   es: Nueva York, en: New York\n"
+            ]
+        );
+        let (output, _) = parse(&["zip", "11106", "-c", "us", "--output", "json"])?
+            .get_output(&api, now)
+            .await?;
+        let locations: Vec<GeoLocation> = serde_json::from_str(&output[0])?;
+        assert_eq!(locations.len(), 1);
+        assert_eq!(
+            locations[0].zip.as_ref().map(StringType::as_str),
+            Some("11106")
+        );
+        assert!(
+            parse(&["zip", "11106", "-c", "xx"])?
+                .get_output(&api, now)
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_prompt_pick() -> Result<(), Error> {
+        let locations: Vec<GeoLocation> =
+            serde_json::from_str(include_str!("../tests/geo_direct.json"))?;
+        let candidates: Vec<_> = (1..).zip(locations).collect();
+
+        let mut prompt = Vec::new();
+        let Err(Error::InvalidValue(e)) =
+            prompt_pick(&candidates, false, &b"1\n"[..], &mut prompt).await
+        else {
+            panic!("expected an error when the input is not a terminal");
+        };
+        assert_eq!(e, "found 2 locations, use --pick N to choose one");
+        assert!(prompt.is_empty());
+
+        let pick = prompt_pick(&candidates, true, &b" 2\n"[..], &mut prompt).await?;
+        assert_eq!(pick, 2);
+        let prompt = String::from_utf8_lossy(&prompt);
+        assert!(prompt.starts_with("1. Astoria, New York, US"));
+        assert!(prompt.ends_with("Pick a location [1-2]: "));
+
+        assert!(matches!(
+            prompt_pick(&candidates, true, &b"two\n"[..], Vec::new()).await,
+            Err(Error::InvalidValue(e)) if &e == "invalid choice two"
+        ));
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_run_opts_config_show() -> Result<(), Error> {
+        let opts = WeatherOpts::try_parse_from(["weather-util-rust", "config", "show"]).unwrap();
+        assert_eq!(
+            opts.command,
+            Some(WeatherCommand::Config(ConfigCommand::Show))
+        );
+        let config = Config::default();
+        let (output, exit_code) = opts.run_opts(&config).await?;
+        assert_eq!(output, vec![config.show()?]);
+        assert_eq!(exit_code, ExitCode::SUCCESS);
+        Ok(())
+    }
+
     #[cfg(feature = "cli")]
     #[tokio::test]
     async fn test_run_opts() -> Result<(), Error> {
@@ -237,9 +1427,9 @@ mod test {
 
         let mut opts = WeatherOpts::default();
         opts.zipcode = Some(55427);
-        opts.apply_defaults(&config);
+        opts.apply_defaults(&config)?;
 
-        let output = opts.run_opts(&config).await?;
+        let (output, _) = opts.run_opts(&config).await?;
 
         assert_eq!(output.len(), 1);
         println!("{}", output[0]);
@@ -249,7 +1439,7 @@ mod test {
         );
 
         opts.forecast = true;
-        let output = opts.run_opts(&config).await?;
+        let (output, _) = opts.run_opts(&config).await?;
         assert!(output.len() == 7 || output.len() == 8);
         info!("{:#?}", output);
         assert!(output[1].contains("Forecast:"));
@@ -259,6 +1449,457 @@ mod test {
         Ok(())
     }
 
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_get_output() -> Result<(), Error> {
+        let transport = FixtureTransport::new()
+            .with_fixture("weather", &[], include_str!("../tests/weather.json"))
+            .with_fixture("forecast", &[], include_str!("../tests/forecast.json"))
+            .with_fixture(
+                "air_pollution",
+                &[],
+                include_str!("../tests/air_pollution.json"),
+            )
+            .with_fixture("zip", &[], include_str!("../tests/geo_zip.json"));
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport);
+
+        let mut opts = WeatherOpts {
+            zipcode: Some(11106),
+            ..WeatherOpts::default()
+        };
+        let now = datetime!(2024-03-14 16:00:00 UTC);
+        let (output, exit_code) = opts.get_output(&api, now).await?;
+        assert_eq!(output.len(), 1);
+        assert!(output[0].starts_with("Current conditions Astoria US"));
+        assert_eq!(exit_code, ExitCode::SUCCESS);
+
+        opts.forecast = true;
+        opts.air_quality = true;
+        let (output, _) = opts.get_output(&api, now).await?;
+        assert_eq!(output.len(), 9);
+        assert!(output[1].contains("Air Quality Index: 2 (Fair)"));
+        assert!(output[2].contains("Forecast:"));
+        assert!(output[3].contains("High:"));
+
+        opts.icons = Some(IconStyle::Ascii);
+        let (output, _) = opts.get_output(&api, now).await?;
+        assert!(output[0].contains("\tConditions: ~~~ broken clouds\n"));
+        assert!(output[3].contains(" ~~~ High:"));
+
+        opts.pressure_unit = Some(PressureUnit::Inhg);
+        let (output, _) = opts.get_output(&api, now).await?;
+        assert!(output[0].contains("\tPressure: 29.97 inHg\n"));
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_get_output_hourly() -> Result<(), Error> {
+        let transport = FixtureTransport::new()
+            .with_fixture("weather", &[], include_str!("../tests/weather.json"))
+            .with_fixture("forecast", &[], include_str!("../tests/forecast_full.json"));
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport);
+
+        let opts = WeatherOpts::try_parse_from([
+            "weather-util-rust",
+            "--lat",
+            "40.76",
+            "--lon=-73.93",
+            "--hourly",
+            "--hours",
+            "6",
+        ])
+        .unwrap();
+        let now = datetime!(2022-02-28 03:00:00 UTC);
+        let (output, _) = opts.get_output(&api, now).await?;
+        assert_eq!(output.len(), 4);
+        assert_eq!(output[1], "\nHourly Forecast:\n");
+        assert!(output[3].contains("light rain"));
+        assert!(!output.iter().any(|l| l.contains("High:")));
+
+        assert!(WeatherOpts::try_parse_from(["weather-util-rust", "--hours", "6"]).is_err());
+
+        let opts = WeatherOpts {
+            hourly: false,
+            hours: None,
+            chart: true,
+            ..opts
+        };
+        let (output, _) = opts.get_output(&api, now).await?;
+        assert!(output[1].starts_with("\nForecast Chart"));
+        assert!(output.iter().any(|l| l.contains('┬')));
+        assert!(!output.iter().any(|l| l.contains("High:")));
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_get_output_format() -> Result<(), Error> {
+        let transport = FixtureTransport::new()
+            .with_fixture("weather", &[], include_str!("../tests/weather.json"))
+            .with_fixture("forecast", &[], include_str!("../tests/forecast_full.json"));
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport);
+        let now = datetime!(2022-02-28 03:00:00 UTC);
+
+        let opts = WeatherOpts::try_parse_from([
+            "weather-util-rust",
+            "--lat",
+            "40.76",
+            "--lon=-73.93",
+            "--format",
+            "{name}: {temp_c:.0}°C {condition} {wind_compass} {wind_kmh:.0}km/h",
+        ])
+        .unwrap();
+        let (output, _) = opts.get_output(&api, now).await?;
+        assert_eq!(output, vec!["Astoria: 4°C broken clouds N 19km/h\n"]);
+
+        let _env = TestEnvs::new(&["TEMPLATE_BAR", "WEATHER_TEMPLATE_BAR"]);
+        unsafe {
+            set_var("TEMPLATE_BAR", "bar from another tool");
+            set_var(
+                "WEATHER_TEMPLATE_BAR",
+                "{icon} {temp_f:.0}F {day1_weekday} {day1_low_f:.0}F",
+            );
+        }
+        let config = Config::init_config(None)?;
+        drop(_env);
+
+        let mut opts = WeatherOpts::try_parse_from([
+            "weather-util-rust",
+            "--lat",
+            "40.76",
+            "--lon=-73.93",
+            "--format",
+            "bar",
+            "--icons",
+            "ascii",
+        ])
+        .unwrap();
+        opts.apply_defaults(&config)?;
+        let (output, _) = opts.get_output(&api, now).await?;
+        assert_eq!(output, vec!["~~~ 38F Mon 30F\n"]);
+
+        let mut typo =
+            WeatherOpts::try_parse_from(["weather-util-rust", "--format", "baz"]).unwrap();
+        assert!(matches!(
+            typo.apply_defaults(&config),
+            Err(Error::InvalidValue(e)) if &e == "unknown template baz"
+        ));
+
+        let opts = WeatherOpts {
+            format: Some("{temp_x}".into()),
+            ..opts
+        };
+        assert!(opts.get_output(&api, now).await.is_err());
+        assert!(
+            WeatherOpts::try_parse_from([
+                "weather-util-rust",
+                "--format",
+                "{temp_c}",
+                "--date",
+                "2024-03-01"
+            ])
+            .is_err()
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_get_output_lang() -> Result<(), Error> {
+        let transport = FixtureTransport::new().with_fixture(
+            "weather",
+            &[("lang", "de")],
+            include_str!("../tests/weather.json"),
+        );
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport.clone())
+            .with_language(Language::De);
+
+        let opts = WeatherOpts::try_parse_from([
+            "weather-util-rust",
+            "--lat",
+            "40.76",
+            "--lon=-73.93",
+            "--lang",
+            "de",
+            "--units",
+            "metric",
+        ])
+        .unwrap();
+        assert_eq!(opts.get_lang(), Language::De);
+        let (output, _) = opts
+            .get_output(&api, datetime!(2022-02-28 03:00:00 UTC))
+            .await?;
+        assert!(output[0].starts_with("Aktuelles Wetter Astoria US 40,76"));
+        assert!(output[0].contains("\tTemperatur: 3,61 °C\n"));
+        assert!(transport.requests()[0].as_str().ends_with("&lang=de"));
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_get_output_all_locations() -> Result<(), Error> {
+        let transport = FixtureTransport::new()
+            .with_fixture("weather", &[], include_str!("../tests/weather.json"))
+            .with_response(
+                "weather",
+                &[("q", "Nowhere")],
+                TransportResponse::new(404, r#"{"cod":"404","message":"city not found"}"#),
+            );
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport.clone());
+        let now = datetime!(2022-02-28 03:00:00 UTC);
+
+        let _env = TestEnvs::new(&[
+            "LOCATION_HOME",
+            "LOCATION_BERLIN_OFFICE",
+            "LOCATION_NOWHERE",
+            "UNITS",
+        ]);
+        unsafe {
+            set_var("LOCATION_HOME", "zipcode=11106;units=imperial");
+            set_var("LOCATION_NOWHERE", "city_name=Nowhere");
+            set_var(
+                "LOCATION_BERLIN_OFFICE",
+                "city_name=Berlin,DE;units=metric;lang=de",
+            );
+            set_var("UNITS", "standard");
+        }
+        let config = Config::init_config(None)?;
+        drop(_env);
+
+        let mut opts =
+            WeatherOpts::try_parse_from(["weather-util-rust", "--location", "berlin-office"])
+                .unwrap();
+        opts.apply_defaults(&config)?;
+        assert_eq!(
+            opts.get_location()?,
+            WeatherLocation::from_city_name("Berlin,DE")
+        );
+        assert_eq!(opts.units, Some(Units::Metric));
+        assert_eq!(opts.get_lang(), Language::De);
+
+        let mut opts = WeatherOpts::try_parse_from([
+            "weather-util-rust",
+            "--location",
+            "home",
+            "--units",
+            "metric",
+        ])
+        .unwrap();
+        opts.apply_defaults(&config)?;
+        assert_eq!(opts.get_location()?, WeatherLocation::from_zipcode(11106));
+        assert_eq!(opts.units, Some(Units::Metric));
+
+        let mut opts =
+            WeatherOpts::try_parse_from(["weather-util-rust", "--location", "paris"]).unwrap();
+        assert!(matches!(
+            opts.apply_defaults(&config),
+            Err(Error::InvalidValue(e))
+                if &e == "unknown location paris, expected one of [berlin-office, home, nowhere]"
+        ));
+        assert!(
+            WeatherOpts::try_parse_from([
+                "weather-util-rust",
+                "--location",
+                "home",
+                "--zipcode",
+                "11106"
+            ])
+            .is_err()
+        );
+
+        let mut opts =
+            WeatherOpts::try_parse_from(["weather-util-rust", "--all-locations"]).unwrap();
+        opts.apply_defaults(&config)?;
+        let (output, _) = opts.get_output(&api, now).await?;
+        assert_eq!(output.len(), 1);
+        let lines: Vec<_> = output[0].lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Location       Temperature  Humidity  Wind"));
+        assert!(lines[1].starts_with("berlin-office  3,6 °C"));
+        assert!(lines[1].ends_with("broken clouds"));
+        assert!(lines[2].starts_with("home           38.5 °F"));
+        assert!(lines[2].contains(" 41%"));
+        assert!(lines[3].starts_with("nowhere  "));
+        assert!(lines[3].contains("error: "), "{}", lines[3]);
+        assert!(lines[3].contains("city not found"), "{}", lines[3]);
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().any(|url| url.as_str().contains("lang=de")));
+
+        opts.output = OutputFormat::Json;
+        let (output, _) = opts.get_output(&api, now).await?;
+        let mut reports: BTreeMap<String, serde_json::Value> = serde_json::from_str(&output[0])?;
+        assert_eq!(reports.len(), 3);
+        assert!(reports["nowhere"]["error"].is_string());
+        let home: WeatherReport = serde_json::from_value(reports.remove("home").unwrap())?;
+        assert_eq!(
+            home.location.name.as_ref().map(StringType::as_str),
+            Some("Astoria")
+        );
+
+        let mut opts =
+            WeatherOpts::try_parse_from(["weather-util-rust", "--all-locations"]).unwrap();
+        opts.apply_defaults(&Config::default())?;
+        assert!(matches!(
+            opts.get_output(&api, now).await,
+            Err(Error::InvalidValue(e)) if e.starts_with("no named locations")
+        ));
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_get_output_alerts() -> Result<(), Error> {
+        let transport = FixtureTransport::new()
+            .with_fixture("weather", &[], include_str!("../tests/weather.json"))
+            .with_fixture(
+                "onecall",
+                &[("exclude", "current,minutely,hourly,daily")],
+                include_str!("../tests/onecall.json"),
+            );
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport);
+
+        let opts = WeatherOpts {
+            lat: Some(Latitude::try_from(40.7608)?),
+            lon: Some(Longitude::try_from(-73.9295)?),
+            alerts: true,
+            ..WeatherOpts::default()
+        };
+        let (output, exit_code) = opts
+            .get_output(&api, datetime!(2024-03-14 16:00:00 UTC))
+            .await?;
+        assert_eq!(output.len(), 3);
+        assert_eq!(output[1], "\nActive Alerts:\n");
+        assert!(output[2].contains("Wind Advisory (NWS Upton NY)"));
+        assert_eq!(exit_code, ExitCode::from(ALERTS_ACTIVE_EXIT_CODE));
+
+        let (output, exit_code) = opts
+            .get_output(&api, datetime!(2024-03-16 16:00:00 UTC))
+            .await?;
+        assert_eq!(output[1], "\nNo Active Alerts\n");
+        assert_eq!(exit_code, ExitCode::SUCCESS);
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_get_output_date() -> Result<(), Error> {
+        let transport = FixtureTransport::new()
+            .with_fixture(
+                "onecall/timemachine",
+                &[("dt", "1710432000")],
+                include_str!("../tests/onecall_timemachine.json"),
+            )
+            .with_fixture(
+                "onecall/day_summary",
+                &[("date", "2024-03-14")],
+                include_str!("../tests/onecall_day_summary.json"),
+            )
+            .with_fixture("zip", &[], include_str!("../tests/geo_zip.json"));
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport.clone());
+
+        let opts = WeatherOpts {
+            zipcode: Some(11106),
+            date: Some(parse_date("2024-03-14")?),
+            ..WeatherOpts::default()
+        };
+        let (output, exit_code) = opts
+            .get_output(&api, datetime!(2024-03-16 16:00:00 UTC))
+            .await?;
+        assert_eq!(output.len(), 2);
+        assert!(output[0].starts_with("Historical conditions 40.76080N -73.92950E"));
+        assert!(output[1].starts_with("Summary for 2024-03-14"));
+        assert_eq!(exit_code, ExitCode::SUCCESS);
+        assert_eq!(transport.requests().len(), 3);
+
+        assert!(parse_date("2024-02-30").is_err());
+        assert!(parse_date("03/14/2024").is_err());
+        for flag in ["--forecast", "--hourly", "--chart", "--air-quality"] {
+            assert!(
+                WeatherOpts::try_parse_from(["weather-util-rust", "--date", "2024-03-14", flag])
+                    .is_err()
+            );
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_get_output_json() -> Result<(), Error> {
+        let transport = FixtureTransport::new()
+            .with_fixture("weather", &[], include_str!("../tests/weather.json"))
+            .with_fixture("forecast", &[], include_str!("../tests/forecast.json"))
+            .with_fixture("zip", &[], include_str!("../tests/geo_zip.json"));
+        let api = WeatherApi::new("8675309", "test.local", "data/2.5/", "geo/1.0/")
+            .with_transport(transport);
+
+        let mut opts = WeatherOpts {
+            zipcode: Some(11106),
+            forecast: true,
+            output: OutputFormat::Json,
+            ..WeatherOpts::default()
+        };
+        let now = datetime!(2024-03-14 16:00:00 UTC);
+        let (output, _) = opts.get_output(&api, now).await?;
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].lines().count(), 1);
+        let value: serde_json::Value = serde_json::from_str(&output[0])?;
+        assert_eq!(value["location"]["name"], "Astoria");
+        assert!(value["current"]["temperature_c"].is_f64());
+        assert!(value["current"]["wind_speed_mps"].is_f64());
+        assert_eq!(value["forecast"].as_array().map(Vec::len), Some(6));
+
+        opts.output = OutputFormat::JsonPretty;
+        let (output, _) = opts.get_output(&api, now).await?;
+        assert!(output[0].lines().count() > 1);
+        let report: WeatherReport = serde_json::from_str(&output[0])?;
+        assert_eq!(report.forecast.map(|f| f.len()), Some(6));
+
+        assert_eq!(
+            OutputFormat::from_str("json-pretty", false),
+            Ok(OutputFormat::JsonPretty)
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_get_units() {
+        let opts = WeatherOpts::default();
+        assert_eq!(opts.get_units(), UnitSystem::default());
+
+        let opts = WeatherOpts {
+            units: Some(Units::Metric),
+            speed_unit: Some(SpeedUnit::Mps),
+            ..WeatherOpts::default()
+        };
+        assert_eq!(
+            opts.get_units(),
+            UnitSystem::from(Units::Metric).with_speed(SpeedUnit::Mps)
+        );
+
+        let opts = WeatherOpts::try_parse_from([
+            "weather-util-rust",
+            "--units",
+            "si",
+            "--temperature-unit",
+            "c",
+        ])
+        .unwrap();
+        assert_eq!(
+            opts.get_units(),
+            UnitSystem::from(Units::Standard).with_temperature(TemperatureUnit::Celsius)
+        );
+    }
+
     #[test]
     fn test_api_help_msg() -> Result<(), Error> {
         let msg = WeatherOpts::api_help_msg();
@@ -0,0 +1,508 @@
+use serde::{Deserialize, Serialize};
+use time::{Date, OffsetDateTime, UtcOffset};
+
+use crate::{
+    StringType,
+    air_quality::AirQuality,
+    beaufort::Beaufort,
+    concentration::Concentration,
+    one_call::{OneCallAlert, OneCallCurrent, OneCallDaySummary, OneCallTimeMachine},
+    precipitation::Precipitation,
+    weather_data::{WeatherCond, WeatherData},
+    weather_forecast::{DayBoundary, WeatherForecast},
+};
+
+/// Version of the report layout, incremented on incompatible changes
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn get_conditions(weather: &[WeatherCond]) -> StringType {
+    weather
+        .first()
+        .map(|w| w.description.clone())
+        .unwrap_or_default()
+}
+
+fn get_one_hour(precip: Option<Precipitation>) -> Option<f64> {
+    precip.map(|p| round2(p.millimeters()))
+}
+
+/// Resolved location
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReportLocation {
+    pub name: Option<StringType>,
+    pub country: Option<StringType>,
+    pub lat: f64,
+    pub lon: f64,
+    /// Offset from UTC in seconds
+    pub timezone_offset_s: i32,
+}
+
+/// Current (or historical) conditions
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReportCurrent {
+    #[serde(with = "time::serde::rfc3339")]
+    pub time: OffsetDateTime,
+    pub temperature_c: f64,
+    pub feels_like_c: f64,
+    pub humidity_percent: i64,
+    pub pressure_hpa: f64,
+    pub wind_speed_mps: f64,
+    pub wind_direction_deg: Option<f64>,
+    pub wind_gust_mps: Option<f64>,
+    pub wind_beaufort: Beaufort,
+    pub conditions: StringType,
+    pub visibility_m: Option<f64>,
+    pub rain_1h_mm: Option<f64>,
+    pub snow_1h_mm: Option<f64>,
+    /// Missing during polar day and polar night
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub sunrise: Option<OffsetDateTime>,
+    /// Missing during polar day and polar night
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub sunset: Option<OffsetDateTime>,
+}
+
+impl ReportCurrent {
+    fn from_one_call(current: &OneCallCurrent, offset: UtcOffset) -> Self {
+        Self {
+            time: current.dt.to_offset(offset),
+            temperature_c: round2(current.temp.celcius()),
+            feels_like_c: round2(current.feels_like.celcius()),
+            humidity_percent: current.humidity.into_inner(),
+            pressure_hpa: round2(current.pressure.hpa()),
+            wind_speed_mps: round2(current.wind_speed.mps()),
+            wind_direction_deg: Some(round2(current.wind_deg.deg())),
+            wind_gust_mps: current.wind_gust.map(|g| round2(g.mps())),
+            wind_beaufort: current.wind_speed.beaufort(),
+            conditions: get_conditions(&current.weather),
+            visibility_m: current.visibility.map(|v| round2(v.meters())),
+            rain_1h_mm: get_one_hour(current.rain.and_then(|r| r.one_hour)),
+            snow_1h_mm: get_one_hour(current.snow.and_then(|s| s.one_hour)),
+            sunrise: current.sunrise.map(|t| t.to_offset(offset)),
+            sunset: current.sunset.map(|t| t.to_offset(offset)),
+        }
+    }
+}
+
+/// Forecast totals for one day
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReportForecastDay {
+    pub date: Date,
+    pub high_c: f64,
+    pub low_c: f64,
+    pub rain_mm: f64,
+    pub snow_mm: f64,
+}
+
+/// One forecast entry (usually covering 3 hours)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReportHourly {
+    #[serde(with = "time::serde::rfc3339")]
+    pub time: OffsetDateTime,
+    pub temperature_c: f64,
+    pub feels_like_c: f64,
+    pub humidity_percent: i64,
+    pub wind_speed_mps: Option<f64>,
+    pub wind_direction_deg: Option<f64>,
+    pub wind_gust_mps: Option<f64>,
+    pub precipitation_probability_percent: Option<f64>,
+    pub rain_mm: Option<f64>,
+    pub snow_mm: Option<f64>,
+    pub conditions: StringType,
+}
+
+/// Totals for one past day, from the One Call `day_summary` api
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReportDaySummary {
+    pub date: Date,
+    pub high_c: f64,
+    pub low_c: f64,
+    pub precipitation_mm: f64,
+    pub max_wind_speed_mps: f64,
+    pub max_wind_direction_deg: f64,
+}
+
+/// Air quality index and pollutant concentrations
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReportAirQuality {
+    #[serde(with = "time::serde::rfc3339")]
+    pub time: OffsetDateTime,
+    /// Air Quality Index from 1 (Good) to 5 (Very Poor)
+    pub aqi: u8,
+    pub aqi_description: StringType,
+    pub pm2_5_ug_m3: f64,
+    pub pm10_ug_m3: f64,
+    pub o3_ug_m3: f64,
+    pub no2_ug_m3: f64,
+    pub no_ug_m3: f64,
+    pub so2_ug_m3: f64,
+    pub co_ug_m3: f64,
+    pub nh3_ug_m3: f64,
+}
+
+/// Active weather alert
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReportAlert {
+    pub event: StringType,
+    pub sender: StringType,
+    #[serde(with = "time::serde::rfc3339")]
+    pub start: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub end: OffsetDateTime,
+    pub description: StringType,
+    pub tags: Vec<StringType>,
+}
+
+/// Stable, unit-explicit json document produced by `--output json`, combining
+/// location, current conditions and the optional sections.
+///
+/// Field names carry their unit (`_c` Celsius, `_hpa` hectopascal, `_mps`
+/// meters per second, `_deg` degrees, `_m` meters, `_mm` millimeters,
+/// `_ug_m3` micrograms per cubic meter, `_percent`), times are RFC 3339 in
+/// the timezone of the location and dates are `YYYY-MM-DD`.
+/// Derived values are rounded to two decimal places, optional sections are
+/// omitted when they were not requested.
+///
+/// ```json
+/// {
+///   "schema_version": 1,
+///   "location": {"name": "Astoria", "country": "US", "lat": 40.76056,
+///                "lon": -73.92944, "timezone_offset_s": -18000},
+///   "current": {"time": "2022-02-27T21:57:31-05:00", "temperature_c": 3.61,
+///               "feels_like_c": -0.58, "humidity_percent": 41,
+///               "pressure_hpa": 1015.0, "wind_speed_mps": 5.36,
+///               "wind_direction_deg": 0.0, "wind_gust_mps": null,
+///               "wind_beaufort": 3, "conditions": "broken clouds",
+///               "visibility_m": 10000.0, "rain_1h_mm": null,
+///               "snow_1h_mm": null, "sunrise": "2022-02-27T06:32:59-05:00",
+///               "sunset": "2022-02-27T17:44:14-05:00"},
+///   "forecast": [{"date": "2022-02-27", "high_c": 3.6, "low_c": 1.9,
+///                 "rain_mm": 0.0, "snow_mm": 0.0}]
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WeatherReport {
+    pub schema_version: u32,
+    pub location: ReportLocation,
+    pub current: ReportCurrent,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forecast: Option<Vec<ReportForecastDay>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hourly: Option<Vec<ReportHourly>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub day_summary: Option<ReportDaySummary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub air_quality: Option<ReportAirQuality>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alerts: Option<Vec<ReportAlert>>,
+}
+
+impl WeatherReport {
+    /// Report for the current conditions in `data`
+    /// ```
+    /// # use anyhow::Error;
+    /// # use std::io::Read;
+    /// # use std::fs::File;
+    /// use weather_util_rust::{weather_data::WeatherData, weather_report::WeatherReport};
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut buf = String::new();
+    /// # let mut f = File::open("tests/weather.json")?;
+    /// # f.read_to_string(&mut buf)?;
+    /// let data: WeatherData = serde_json::from_str(&buf)?;
+    ///
+    /// let report = WeatherReport::from_weather_data(&data);
+    /// let value = serde_json::to_value(&report)?;
+    ///
+    /// assert_eq!(value["location"]["name"], "Astoria");
+    /// assert_eq!(value["current"]["humidity_percent"], 41);
+    /// assert!(value.get("forecast").is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn from_weather_data(data: &WeatherData) -> Self {
+        let offset = data.get_offset();
+        let location = ReportLocation {
+            name: Some(data.name.clone()).filter(|n| !n.is_empty()),
+            country: data.sys.country.clone(),
+            lat: data.coord.lat.into(),
+            lon: data.coord.lon.into(),
+            timezone_offset_s: data.timezone.into_inner(),
+        };
+        let current = ReportCurrent {
+            time: data.get_dt(),
+            temperature_c: round2(data.main.temp.celcius()),
+            feels_like_c: round2(data.main.feels_like.celcius()),
+            humidity_percent: data.main.humidity.into_inner(),
+            pressure_hpa: round2(data.main.pressure.hpa()),
+            wind_speed_mps: round2(data.wind.speed.mps()),
+            wind_direction_deg: data.wind.deg.map(|d| round2(d.deg())),
+            wind_gust_mps: data.wind.gust.map(|g| round2(g.mps())),
+            wind_beaufort: data.wind.speed.beaufort(),
+            conditions: get_conditions(&data.weather),
+            visibility_m: data.visibility.map(|v| round2(v.meters())),
+            rain_1h_mm: get_one_hour(data.rain.and_then(|r| r.one_hour)),
+            snow_1h_mm: get_one_hour(data.snow.and_then(|s| s.one_hour)),
+            sunrise: Some(data.sys.sunrise.to_offset(offset)),
+            sunset: Some(data.sys.sunset.to_offset(offset)),
+        };
+        Self {
+            schema_version: REPORT_SCHEMA_VERSION,
+            location,
+            current,
+            forecast: None,
+            hourly: None,
+            day_summary: None,
+            air_quality: None,
+            alerts: None,
+        }
+    }
+
+    /// Report for the historical conditions in `data`, returns `None` if the
+    /// response contains no data
+    #[must_use]
+    pub fn from_time_machine(data: &OneCallTimeMachine) -> Option<Self> {
+        let offset = data.get_offset();
+        let current = data.data.first()?;
+        let location = ReportLocation {
+            name: None,
+            country: None,
+            lat: data.lat.into(),
+            lon: data.lon.into(),
+            timezone_offset_s: data.timezone_offset.into_inner(),
+        };
+        Some(Self {
+            schema_version: REPORT_SCHEMA_VERSION,
+            location,
+            current: ReportCurrent::from_one_call(current, offset),
+            forecast: None,
+            hourly: None,
+            day_summary: None,
+            air_quality: None,
+            alerts: None,
+        })
+    }
+
+    #[must_use]
+    pub fn with_forecast(self, forecast: &WeatherForecast, boundary: DayBoundary) -> Self {
+        let forecast = forecast
+            .get_daily_summaries(boundary)
+            .into_values()
+            .map(|summary| ReportForecastDay {
+                date: summary.date,
+                high_c: round2(summary.high.celcius()),
+                low_c: round2(summary.low.celcius()),
+                rain_mm: round2(summary.rain.millimeters()),
+                snow_mm: round2(summary.snow.millimeters()),
+            })
+            .collect();
+        Self {
+            forecast: Some(forecast),
+            ..self
+        }
+    }
+
+    /// Add forecast entries ending after `now` and starting within `hours` of
+    /// `now`
+    #[must_use]
+    pub fn with_hourly(
+        self,
+        forecast: &WeatherForecast,
+        hours: Option<u64>,
+        now: OffsetDateTime,
+    ) -> Self {
+        let hourly = forecast
+            .get_hourly(hours, now)
+            .into_iter()
+            .map(|h| ReportHourly {
+                time: h.dt,
+                temperature_c: round2(h.temperature.celcius()),
+                feels_like_c: round2(h.feels_like.celcius()),
+                humidity_percent: h.humidity.into_inner(),
+                wind_speed_mps: h.wind.map(|w| round2(w.speed.mps())),
+                wind_direction_deg: h.wind.and_then(|w| w.deg).map(|d| round2(d.deg())),
+                wind_gust_mps: h.wind.and_then(|w| w.gust).map(|g| round2(g.mps())),
+                precipitation_probability_percent: h.pop.map(|p| round2(p * 100.0)),
+                rain_mm: h.rain.map(|r| round2(r.millimeters())),
+                snow_mm: h.snow.map(|s| round2(s.millimeters())),
+                conditions: h.conditions,
+            })
+            .collect();
+        Self {
+            hourly: Some(hourly),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_day_summary(self, summary: &OneCallDaySummary) -> Self {
+        let day_summary = ReportDaySummary {
+            date: summary.date,
+            high_c: round2(summary.temperature.max.celcius()),
+            low_c: round2(summary.temperature.min.celcius()),
+            precipitation_mm: round2(summary.precipitation.total.millimeters()),
+            max_wind_speed_mps: round2(summary.wind.max.speed.mps()),
+            max_wind_direction_deg: round2(summary.wind.max.direction.deg()),
+        };
+        Self {
+            day_summary: Some(day_summary),
+            ..self
+        }
+    }
+
+    /// Add the first entry of `air_quality`, if any
+    #[must_use]
+    pub fn with_air_quality(self, air_quality: &AirQuality) -> Self {
+        let offset = self.get_offset();
+        let air_quality = air_quality.list.first().map(|entry| {
+            let c = &entry.components;
+            let value = |v: Concentration| round2(v.micrograms_per_cubic_meter());
+            ReportAirQuality {
+                time: entry.dt.to_offset(offset),
+                aqi: entry.main.aqi.into(),
+                aqi_description: entry.main.aqi.description().into(),
+                pm2_5_ug_m3: value(c.pm2_5),
+                pm10_ug_m3: value(c.pm10),
+                o3_ug_m3: value(c.o3),
+                no2_ug_m3: value(c.no2),
+                no_ug_m3: value(c.no),
+                so2_ug_m3: value(c.so2),
+                co_ug_m3: value(c.co),
+                nh3_ug_m3: value(c.nh3),
+            }
+        });
+        Self {
+            air_quality,
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_alerts(self, alerts: &[&OneCallAlert]) -> Self {
+        let offset = self.get_offset();
+        let alerts = alerts
+            .iter()
+            .map(|alert| ReportAlert {
+                event: alert.event.clone(),
+                sender: alert.sender_name.clone(),
+                start: alert.start.to_offset(offset),
+                end: alert.end.to_offset(offset),
+                description: alert.description.clone(),
+                tags: alert.tags.clone(),
+            })
+            .collect();
+        Self {
+            alerts: Some(alerts),
+            ..self
+        }
+    }
+
+    fn get_offset(&self) -> UtcOffset {
+        UtcOffset::from_whole_seconds(self.location.timezone_offset_s).unwrap_or(UtcOffset::UTC)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use crate::{
+        Error,
+        air_quality::AirQuality,
+        one_call::{OneCall, OneCallDaySummary, OneCallTimeMachine},
+        weather_data::WeatherData,
+        weather_forecast::{DayBoundary, WeatherForecast},
+        weather_report::{REPORT_SCHEMA_VERSION, WeatherReport},
+    };
+
+    #[test]
+    fn test_weather_report() -> Result<(), Error> {
+        let data: WeatherData = serde_json::from_str(include_str!("../tests/weather.json"))?;
+        let forecast: WeatherForecast =
+            serde_json::from_str(include_str!("../tests/forecast.json"))?;
+        let air_quality: AirQuality =
+            serde_json::from_str(include_str!("../tests/air_pollution.json"))?;
+        let one_call: OneCall = serde_json::from_str(include_str!("../tests/onecall.json"))?;
+        let alerts = one_call.get_active_alerts(datetime!(2024-03-14 16:00:00 UTC));
+
+        let report = WeatherReport::from_weather_data(&data)
+            .with_forecast(&forecast, DayBoundary::Midnight)
+            .with_air_quality(&air_quality)
+            .with_alerts(&alerts);
+        assert_eq!(report.schema_version, REPORT_SCHEMA_VERSION);
+        assert_eq!(report.location.name.as_deref(), Some(&"Astoria".into()));
+        assert_eq!(report.location.timezone_offset_s, -18000);
+        assert_eq!(report.current.time.offset().whole_hours(), -5);
+
+        let value = serde_json::to_value(&report)?;
+        let current = &value["current"];
+        assert!(current["temperature_c"].is_f64());
+        assert!(current["wind_speed_mps"].is_f64());
+        assert_eq!(current["wind_beaufort"], 3);
+        assert!(current["time"].as_str().unwrap().ends_with("-05:00"));
+        let forecast = value["forecast"].as_array().unwrap();
+        assert_eq!(forecast[0]["date"], "2022-02-27");
+        assert!(forecast[0]["high_c"].is_f64());
+        assert_eq!(value["air_quality"]["aqi"], 2);
+        assert_eq!(value["air_quality"]["aqi_description"], "Fair");
+        assert_eq!(value["air_quality"]["pm2_5_ug_m3"], 11.52);
+        assert_eq!(value["alerts"][0]["event"], "Wind Advisory");
+        assert!(value.get("day_summary").is_none());
+
+        let serialized = serde_json::to_string(&report)?;
+        let report2: WeatherReport = serde_json::from_str(&serialized)?;
+        assert_eq!(report2, report);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hourly_report() -> Result<(), Error> {
+        let data: WeatherData = serde_json::from_str(include_str!("../tests/weather.json"))?;
+        let forecast: WeatherForecast =
+            serde_json::from_str(include_str!("../tests/forecast_full.json"))?;
+        let report = WeatherReport::from_weather_data(&data).with_hourly(
+            &forecast,
+            Some(6),
+            datetime!(2022-02-28 03:00:00 UTC),
+        );
+        assert_eq!(report.forecast, None);
+        let hourly = report.hourly.unwrap();
+        assert_eq!(hourly.len(), 2);
+        assert_eq!(hourly[1].time, datetime!(2022-02-28 01:00:00 -05:00));
+        assert_eq!(hourly[1].precipitation_probability_percent, Some(38.0));
+        assert_eq!(hourly[1].rain_mm, Some(0.41));
+        assert_eq!(hourly[1].wind_gust_mps, Some(8.9));
+        assert_eq!(hourly[1].conditions.as_str(), "light rain");
+        Ok(())
+    }
+
+    #[test]
+    fn test_historical_report() -> Result<(), Error> {
+        let data: OneCallTimeMachine =
+            serde_json::from_str(include_str!("../tests/onecall_timemachine.json"))?;
+        let summary: OneCallDaySummary =
+            serde_json::from_str(include_str!("../tests/onecall_day_summary.json"))?;
+        let report = WeatherReport::from_time_machine(&data)
+            .unwrap()
+            .with_day_summary(&summary);
+        assert_eq!(report.location.name, None);
+        assert_eq!(report.current.time, datetime!(2024-03-14 08:00:00 -04:00));
+        assert_eq!(report.current.temperature_c, 10.25);
+        assert_eq!(report.current.rain_1h_mm, Some(0.3));
+        let day_summary = report.day_summary.unwrap();
+        assert_eq!(day_summary.date, date!(2024 - 03 - 14));
+        assert_eq!(day_summary.high_c, 15.1);
+        assert_eq!(day_summary.precipitation_mm, 2.37);
+
+        let data = OneCallTimeMachine {
+            data: vec![],
+            ..data
+        };
+        assert_eq!(WeatherReport::from_time_machine(&data), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+use futures::future::BoxFuture;
+use parking_lot::Mutex;
+use std::{fs, path::Path, sync::Arc, time::Duration};
+use url::Url;
+
+use crate::{Error, StringType};
+
+#[cfg(feature = "cli")]
+use reqwest::{Client, header::RETRY_AFTER};
+
+#[cfg(feature = "cli")]
+use time::OffsetDateTime;
+
+#[cfg(feature = "cli")]
+use crate::retry_policy::parse_retry_after;
+
+/// Status, body and `Retry-After` delay of a response returned by a
+/// `WeatherTransport`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TransportResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+    pub retry_after: Option<Duration>,
+}
+
+impl TransportResponse {
+    #[must_use]
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+            retry_after: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_retry_after(self, retry_after: Duration) -> Self {
+        Self {
+            retry_after: Some(retry_after),
+            ..self
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Transport used by `WeatherApi` to perform GET requests against the api.
+pub trait WeatherTransport: Send + Sync {
+    /// Perform a GET request for `url`, non-success statuses are returned as
+    /// part of the `TransportResponse` rather than as an error.
+    fn get<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, Result<TransportResponse, Error>>;
+}
+
+/// Default transport, sends requests using a `reqwest` Client
+#[cfg(feature = "cli")]
+#[derive(Default, Clone)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+#[cfg(feature = "cli")]
+impl ReqwestTransport {
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(feature = "cli")]
+impl WeatherTransport for ReqwestTransport {
+    fn get<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, Result<TransportResponse, Error>> {
+        Box::pin(async move {
+            let response = self.client.get(url.clone()).send().await?;
+            let status = response.status().as_u16();
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| parse_retry_after(v, OffsetDateTime::now_utc()));
+            let body = response.bytes().await?;
+            Ok(TransportResponse {
+                retry_after,
+                ..TransportResponse::new(status, body)
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Fixture {
+    command: StringType,
+    options: Vec<(StringType, StringType)>,
+    responses: Vec<TransportResponse>,
+}
+
+impl Fixture {
+    fn matches(&self, url: &Url) -> bool {
+        let path = url.path().trim_start_matches('/');
+        let command = self.command.trim_start_matches('/');
+        let command_matches = path == command
+            || (path.ends_with(command) && path[..path.len() - command.len()].ends_with('/'));
+        command_matches
+            && self.options.iter().all(|(key, value)| {
+                url.query_pairs()
+                    .any(|(k, v)| k == key.as_str() && v == value.as_str())
+            })
+    }
+}
+
+/// Transport which replays canned responses (e.g. `tests/weather.json`)
+/// instead of performing requests, intended for offline testing.
+///
+/// Fixtures are keyed by command (the trailing segments of the url path, e.g.
+/// `weather` or `geo/1.0/zip`) and the query options which must be present
+/// in the request, the fixture with the longest command and most options wins.
+/// Requests without a matching fixture get a 404 response.
+/// A fixture may hold a sequence of responses which are served in order, the
+/// last one is repeated once the sequence is exhausted.
+/// ```
+/// use weather_util_rust::weather_transport::FixtureTransport;
+/// # use weather_util_rust::weather_transport::WeatherTransport;
+/// # use url::Url;
+/// # use anyhow::Error;
+/// # use futures::executor::block_on;
+/// # fn main() -> Result<(), Error> {
+/// let transport = FixtureTransport::new()
+///     .with_fixture("weather", &[("q", "Astoria")], include_str!("../tests/weather.json"));
+/// let url = Url::parse("https://example.com/data/2.5/weather?q=Astoria&appid=1234")?;
+/// let response = block_on(transport.get(&url))?;
+/// assert_eq!(response.status, 200);
+/// assert_eq!(transport.requests(), vec![url]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default, Clone)]
+pub struct FixtureTransport {
+    fixtures: Vec<Fixture>,
+    requests: Arc<Mutex<Vec<Url>>>,
+}
+
+impl FixtureTransport {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve `body` with status 200 for requests matching `command` and
+    /// `options`
+    #[must_use]
+    pub fn with_fixture(
+        self,
+        command: &str,
+        options: &[(&str, &str)],
+        body: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.with_response(command, options, TransportResponse::new(200, body))
+    }
+
+    /// Serve `response` for requests matching `command` and `options`
+    #[must_use]
+    pub fn with_response(
+        self,
+        command: &str,
+        options: &[(&str, &str)],
+        response: TransportResponse,
+    ) -> Self {
+        self.with_responses(command, options, vec![response])
+    }
+
+    /// Serve `responses` in order for successive requests matching `command`
+    /// and `options`
+    #[must_use]
+    pub fn with_responses(
+        mut self,
+        command: &str,
+        options: &[(&str, &str)],
+        responses: Vec<TransportResponse>,
+    ) -> Self {
+        self.fixtures.push(Fixture {
+            command: command.into(),
+            options: options
+                .iter()
+                .map(|(k, v)| ((*k).into(), (*v).into()))
+                .collect(),
+            responses,
+        });
+        self
+    }
+
+    /// Serve the contents of the file at `path` for requests matching
+    /// `command` and `options`
+    /// # Errors
+    ///
+    /// Will return error if the file cannot be read
+    pub fn with_fixture_file(
+        self,
+        command: &str,
+        options: &[(&str, &str)],
+        path: &Path,
+    ) -> Result<Self, Error> {
+        let body = fs::read(path)?;
+        Ok(self.with_fixture(command, options, body))
+    }
+
+    /// Urls requested so far, shared between clones of the transport
+    #[must_use]
+    pub fn requests(&self) -> Vec<Url> {
+        self.requests.lock().clone()
+    }
+
+    fn find_fixture(&self, url: &Url) -> Option<usize> {
+        self.fixtures
+            .iter()
+            .enumerate()
+            .filter(|(_, fixture)| fixture.matches(url))
+            .max_by_key(|(_, fixture)| (fixture.command.len(), fixture.options.len()))
+            .map(|(index, _)| index)
+    }
+
+    fn find_response(&self, url: &Url, previous: &[Url]) -> TransportResponse {
+        let Some(index) = self.find_fixture(url) else {
+            return TransportResponse::new(404, r#"{"cod":"404","message":"fixture not found"}"#);
+        };
+        let served = previous
+            .iter()
+            .filter(|u| self.find_fixture(u) == Some(index))
+            .count();
+        let responses = &self.fixtures[index].responses;
+        responses
+            .get(served)
+            .or_else(|| responses.last())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+impl WeatherTransport for FixtureTransport {
+    fn get<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, Result<TransportResponse, Error>> {
+        let mut requests = self.requests.lock();
+        let response = self.find_response(url, &requests);
+        requests.push(url.clone());
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use crate::{
+        Error,
+        weather_transport::{FixtureTransport, TransportResponse},
+    };
+
+    #[test]
+    fn test_fixture_transport() -> Result<(), Error> {
+        let transport = FixtureTransport::new()
+            .with_fixture("weather", &[], "any")
+            .with_fixture("weather", &[("q", "Astoria")], "astoria")
+            .with_fixture("air_pollution/forecast", &[], "air")
+            .with_fixture("forecast", &[], "forecast")
+            .with_response("zip", &[], TransportResponse::new(500, "error"))
+            .with_responses(
+                "onecall",
+                &[],
+                vec![
+                    TransportResponse::new(429, ""),
+                    TransportResponse::new(200, "ok"),
+                ],
+            );
+
+        let url = Url::parse("https://test.local/data/2.5/weather?q=Astoria&appid=1")?;
+        assert_eq!(transport.find_response(&url, &[]).body, b"astoria");
+
+        let url = Url::parse("https://test.local/data/2.5/weather?q=Paris&appid=1")?;
+        assert_eq!(transport.find_response(&url, &[]).body, b"any");
+
+        let url = Url::parse("https://test.local/data/2.5/forecast?q=Paris")?;
+        assert_eq!(transport.find_response(&url, &[]).body, b"forecast");
+
+        let url = Url::parse("https://test.local/data/2.5/air_pollution/forecast?lat=1")?;
+        assert_eq!(transport.find_response(&url, &[]).body, b"air");
+
+        let url = Url::parse("https://test.local/geo/1.0/zip?zip=10001")?;
+        let response = transport.find_response(&url, &[]);
+        assert_eq!(response.status, 500);
+        assert!(!response.is_success());
+
+        let url = Url::parse("https://test.local/data/2.5/xweather")?;
+        assert_eq!(transport.find_response(&url, &[]).status, 404);
+
+        let url = Url::parse("https://test.local/data/3.0/onecall")?;
+        let previous = vec![url.clone()];
+        assert_eq!(transport.find_response(&url, &[]).status, 429);
+        assert_eq!(transport.find_response(&url, &previous).status, 200);
+        let previous = vec![url.clone(), url.clone(), url.clone()];
+        assert_eq!(transport.find_response(&url, &previous).status, 200);
+        Ok(())
+    }
+}